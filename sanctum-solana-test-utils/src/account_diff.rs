@@ -0,0 +1,415 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Write},
+    path::Path,
+};
+
+use data_encoding::HEXLOWER;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::{BanksClient, BanksClientError};
+use solana_sdk::account::Account;
+
+use crate::{
+    test_fixtures_dir, TOKENKEG_PROGRAM_ID, TOKEN_2022_ACCOUNT_TYPE_ACCOUNT, TOKEN_2022_PROGRAM_ID,
+    TOKEN_ACCOUNT_AMOUNT_OFFSET, TOKEN_ACCOUNT_LEN,
+};
+
+/// Set this env var to any value other than `0` or `false` to make
+/// [`AccountsDiff::assert_golden`] write golden files instead of checking against them
+pub const UPDATE_GOLDEN_FILES_ENV_VAR: &str = "UPDATE_GOLDEN_FILES";
+
+/// The state of a set of accounts at a point in time.
+/// `None` if the account did not exist
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountsSnapshot(pub BTreeMap<Pubkey, Option<Account>>);
+
+impl AccountsSnapshot {
+    pub async fn fetch(
+        banks_client: &mut BanksClient,
+        keys: impl IntoIterator<Item = Pubkey>,
+    ) -> Result<Self, BanksClientError> {
+        let mut res = BTreeMap::new();
+        for key in keys {
+            let account = banks_client.get_account(key).await?;
+            res.insert(key, account);
+        }
+        Ok(Self(res))
+    }
+
+    /// Fetches the same set of keys as `self` again
+    pub async fn refetch(&self, banks_client: &mut BanksClient) -> Result<Self, BanksClientError> {
+        Self::fetch(banks_client, self.0.keys().copied()).await
+    }
+
+    /// Diff going from `self` to `after`.
+    /// Keys only present in one of the snapshots are treated as nonexistent in the other
+    pub fn diff(&self, after: &Self) -> AccountsDiff {
+        let mut res = BTreeMap::new();
+        for key in self.0.keys().chain(after.0.keys()) {
+            let before_acc = self.0.get(key).and_then(Option::as_ref);
+            let after_acc = after.0.get(key).and_then(Option::as_ref);
+            if let Some(diff) = AccountDiff::new(before_acc, after_acc) {
+                res.insert(key.to_string(), diff);
+            }
+        }
+        AccountsDiff(res)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// Change in lamports, or in token amount for token accounts
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmountChange {
+    pub before: u64,
+    pub after: u64,
+    pub delta: i128,
+}
+
+impl AmountChange {
+    fn new(before: u64, after: u64) -> Option<Self> {
+        (before != after).then(|| Self {
+            before,
+            after,
+            delta: i128::from(after) - i128::from(before),
+        })
+    }
+}
+
+/// A maximal run of changed bytes in account data.
+/// Bytes past the end of the shorter data are considered changed.
+/// `before` and `after` are hex-encoded and may be shorter than `end - start`
+/// if the account data was resized
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataRangeChange {
+    pub start: usize,
+    pub end: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// How a single account changed. Fields that did not change are `None`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDiff {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existed: Option<Change<bool>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lamports: Option<AmountChange>,
+
+    /// Always set if the account was created or deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Change<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable: Option<Change<bool>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_len: Option<Change<usize>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data: Vec<DataRangeChange>,
+
+    /// Only set if the account is a tokenkeg or token-2022 token account
+    /// both before and after
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_amount: Option<AmountChange>,
+}
+
+impl AccountDiff {
+    /// Returns `None` if nothing changed. Does not consider `rent_epoch`
+    pub fn new(before: Option<&Account>, after: Option<&Account>) -> Option<Self> {
+        let existed = (before.is_some() != after.is_some()).then_some(Change {
+            before: before.is_some(),
+            after: after.is_some(),
+        });
+        // report the owner of created or deleted accounts even though the
+        // missing side's default owner is the system program
+        let existence_changed = existed.is_some();
+        let empty = Account::default();
+        let before = before.unwrap_or(&empty);
+        let after = after.unwrap_or(&empty);
+        let res = Self {
+            existed,
+            lamports: AmountChange::new(before.lamports, after.lamports),
+            owner: (existence_changed || before.owner != after.owner).then(|| Change {
+                before: before.owner.to_string(),
+                after: after.owner.to_string(),
+            }),
+            executable: (before.executable != after.executable).then_some(Change {
+                before: before.executable,
+                after: after.executable,
+            }),
+            data_len: (before.data.len() != after.data.len()).then_some(Change {
+                before: before.data.len(),
+                after: after.data.len(),
+            }),
+            data: diff_data(&before.data, &after.data),
+            token_amount: token_account_amount(before)
+                .zip(token_account_amount(after))
+                .and_then(|(before, after)| AmountChange::new(before, after)),
+        };
+        (res != Self::default()).then_some(res)
+    }
+}
+
+/// Diffs of changed accounts, keyed by base58 pubkey
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountsDiff(pub BTreeMap<String, AccountDiff>);
+
+impl AccountsDiff {
+    pub fn get(&self, key: &Pubkey) -> Option<&AccountDiff> {
+        self.0.get(&key.to_string())
+    }
+
+    pub fn is_changed(&self, key: &Pubkey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Panics if any account other than `keys` changed, or if any of `keys` did not
+    pub fn assert_changed_exactly(&self, keys: &[Pubkey]) {
+        let mut expected: Vec<String> = keys.iter().map(Pubkey::to_string).collect();
+        expected.sort();
+        expected.dedup();
+        assert!(
+            self.0.keys().eq(expected.iter()),
+            "Expected changed accounts {expected:?}. Diff:\n{self}"
+        );
+    }
+
+    /// Checks the diff against the JSON file at `<test_fixtures_dir()>/relative_path`,
+    /// or writes it there if [`UPDATE_GOLDEN_FILES_ENV_VAR`] is set
+    pub fn assert_golden<P: AsRef<Path>>(&self, relative_path: P) {
+        self.assert_golden_file(test_fixtures_dir().join(relative_path))
+    }
+
+    /// [`Self::assert_golden`] with a full path
+    pub fn assert_golden_file<P: AsRef<Path>>(&self, path: P) {
+        let update = std::env::var(UPDATE_GOLDEN_FILES_ENV_VAR)
+            .map(|v| !matches!(v.as_str(), "" | "0" | "false"))
+            .unwrap_or(false);
+        self.check_golden_file(path.as_ref(), update)
+    }
+
+    fn check_golden_file(&self, path: &Path, update: bool) {
+        if update {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).unwrap();
+            }
+            let mut f = File::create(path).unwrap();
+            serde_json::to_writer_pretty(&mut f, self).unwrap();
+            f.write_all(b"\n").unwrap();
+            return;
+        }
+        let golden: Self = match File::open(path) {
+            Ok(f) => serde_json::from_reader(BufReader::new(f))
+                .unwrap_or_else(|e| panic!("Invalid golden file {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => panic!(
+                "Golden file {} does not exist. Rerun with {UPDATE_GOLDEN_FILES_ENV_VAR}=1 to create it. Diff:\n{self}",
+                path.display()
+            ),
+            Err(e) => panic!("Failed to read golden file {}: {e}", path.display()),
+        };
+        assert_eq!(
+            *self,
+            golden,
+            "Diff does not match golden file {}. Rerun with {UPDATE_GOLDEN_FILES_ENV_VAR}=1 to accept. Diff:\n{self}",
+            path.display()
+        );
+    }
+}
+
+impl std::fmt::Display for AccountsDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string_pretty(self).map_err(|_| std::fmt::Error)?)
+    }
+}
+
+/// Snapshots `keys` before and after running `f`
+pub async fn diff_accounts<F, Fut, T>(
+    banks_client: &mut BanksClient,
+    keys: impl IntoIterator<Item = Pubkey>,
+    f: F,
+) -> Result<(T, AccountsDiff), BanksClientError>
+where
+    F: FnOnce(BanksClient) -> Fut,
+    Fut: std::future::Future<Output = T>,
+{
+    let before = AccountsSnapshot::fetch(banks_client, keys).await?;
+    let res = f(banks_client.clone()).await;
+    let after = before.refetch(banks_client).await?;
+    Ok((res, before.diff(&after)))
+}
+
+fn diff_data(before: &[u8], after: &[u8]) -> Vec<DataRangeChange> {
+    let len = before.len().max(after.len());
+    let is_changed = |i: usize| before.get(i) != after.get(i);
+    let mut res = Vec::new();
+    let mut i = 0;
+    while i < len {
+        if !is_changed(i) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < len && is_changed(i) {
+            i += 1;
+        }
+        let slice = |data: &[u8]| {
+            HEXLOWER.encode(data.get(start.min(data.len())..i.min(data.len())).unwrap())
+        };
+        res.push(DataRangeChange {
+            start,
+            end: i,
+            before: slice(before),
+            after: slice(after),
+        });
+    }
+    res
+}
+
+fn token_account_amount(account: &Account) -> Option<u64> {
+    let is_token_account = match account.owner {
+        TOKENKEG_PROGRAM_ID => account.data.len() == TOKEN_ACCOUNT_LEN,
+        TOKEN_2022_PROGRAM_ID => {
+            account.data.len() == TOKEN_ACCOUNT_LEN
+                || account.data.get(TOKEN_ACCOUNT_LEN) == Some(&TOKEN_2022_ACCOUNT_TYPE_ACCOUNT)
+        }
+        _ => false,
+    };
+    if !is_token_account {
+        return None;
+    }
+    let amount = account
+        .data
+        .get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)?;
+    Some(u64::from_le_bytes(amount.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{system_instruction, system_program};
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{signer::Signer, transaction::Transaction};
+
+    use super::*;
+
+    #[test]
+    fn data_ranges() {
+        assert_eq!(
+            diff_data(&[0, 1, 2, 3, 4], &[0, 9, 9, 3, 4, 5]),
+            vec![
+                DataRangeChange {
+                    start: 1,
+                    end: 3,
+                    before: "0102".to_owned(),
+                    after: "0909".to_owned(),
+                },
+                DataRangeChange {
+                    start: 5,
+                    end: 6,
+                    before: "".to_owned(),
+                    after: "05".to_owned(),
+                },
+            ]
+        );
+        assert!(diff_data(&[1, 2], &[1, 2]).is_empty());
+    }
+
+    #[test]
+    fn token_amount() {
+        let mut before = Account::new(1, TOKEN_ACCOUNT_LEN, &TOKENKEG_PROGRAM_ID);
+        let amount = TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8;
+        before.data[amount.clone()].copy_from_slice(&100u64.to_le_bytes());
+        let mut after = before.clone();
+        after.data[amount].copy_from_slice(&30u64.to_le_bytes());
+        let diff = AccountDiff::new(Some(&before), Some(&after)).unwrap();
+        assert_eq!(
+            diff.token_amount,
+            Some(AmountChange {
+                before: 100,
+                after: 30,
+                delta: -70
+            })
+        );
+        assert_eq!(diff.data.len(), 1);
+        assert!(AccountDiff::new(Some(&before), Some(&before)).is_none());
+    }
+
+    #[tokio::test]
+    async fn transfer_diff_and_golden() {
+        let pt = ProgramTest::default();
+        let (mut banks_client, payer, rbh) = pt.start().await;
+        let dst = Pubkey::new_unique();
+        let untouched = Pubkey::new_unique();
+
+        let mut tx = Transaction::new_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &dst,
+                1_000_000_000,
+            )],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], rbh);
+        let (res, diff) = diff_accounts(
+            &mut banks_client,
+            [payer.pubkey(), dst, untouched],
+            |mut bc| async move { bc.process_transaction(tx).await },
+        )
+        .await
+        .unwrap();
+        res.unwrap();
+
+        diff.assert_changed_exactly(&[payer.pubkey(), dst]);
+        let dst_diff = diff.get(&dst).unwrap();
+        assert_eq!(
+            dst_diff.existed,
+            Some(Change {
+                before: false,
+                after: true
+            })
+        );
+        assert_eq!(dst_diff.lamports.unwrap().delta, 1_000_000_000);
+        assert_eq!(
+            dst_diff.owner.as_ref().unwrap().after,
+            system_program::ID.to_string()
+        );
+        assert!(diff.get(&payer.pubkey()).unwrap().lamports.unwrap().delta < -1_000_000_000);
+
+        let dir = tempfile::tempdir().unwrap();
+        let golden = dir.path().join("diffs").join("transfer.json");
+        diff.check_golden_file(&golden, true);
+        diff.check_golden_file(&golden, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match golden file")]
+    fn golden_mismatch_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let golden = dir.path().join("diff.json");
+        let a = Account::new(1, 0, &system_program::ID);
+        let b = Account::new(2, 0, &system_program::ID);
+        let key = Pubkey::new_unique();
+        let diff = |before: &Account, after: &Account| {
+            AccountsSnapshot(BTreeMap::from([(key, Some(before.clone()))])).diff(&AccountsSnapshot(
+                BTreeMap::from([(key, Some(after.clone()))]),
+            ))
+        };
+        diff(&a, &b).check_golden_file(&golden, true);
+        diff(&b, &a).check_golden_file(&golden, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn golden_missing_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        AccountsDiff::default().check_golden_file(&dir.path().join("diff.json"), false);
+    }
+}
@@ -0,0 +1,59 @@
+use jsonrpc_core::ErrorCode;
+use solana_account_decoder::{
+    parse_account_data::SplTokenAdditionalData, UiAccount, UiAccountEncoding, UiDataSliceConfig,
+    MAX_BASE58_BYTES,
+};
+use solana_program::{clock::UnixTimestamp, pubkey::Pubkey};
+use solana_sdk::account::Account;
+use spl_token_2022::{
+    extension::{
+        interest_bearing_mint::InterestBearingConfig, BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::Mint,
+};
+
+/// Same as `solana_rpc::rpc::encode_account`
+pub fn encode_account(
+    pubkey: &Pubkey,
+    account: &Account,
+    encoding: UiAccountEncoding,
+    data_slice: Option<UiDataSliceConfig>,
+) -> Result<UiAccount, jsonrpc_core::Error> {
+    let encoded_len = data_slice.map_or(
+        account.data.len(),
+        |UiDataSliceConfig { offset, length }| {
+            length.min(account.data.len().saturating_sub(offset))
+        },
+    );
+    if matches!(
+        encoding,
+        UiAccountEncoding::Binary | UiAccountEncoding::Base58
+    ) && encoded_len > MAX_BASE58_BYTES
+    {
+        return Err(jsonrpc_core::Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Encoded binary (base 58) data should be less than {MAX_BASE58_BYTES} bytes, please use Base64 encoding."),
+            data: None,
+        });
+    }
+    Ok(UiAccount::encode(
+        pubkey, account, encoding, None, data_slice,
+    ))
+}
+
+/// Same as `solana_rpc::parsed_token_accounts::get_additional_mint_data`.
+///
+/// `unix_timestamp` is the current clock's, used for interest-bearing mints
+pub fn spl_token_additional_data(
+    mint_data: &[u8],
+    unix_timestamp: UnixTimestamp,
+) -> Option<SplTokenAdditionalData> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data).ok()?;
+    Some(SplTokenAdditionalData {
+        decimals: mint.base.decimals,
+        interest_bearing_config: mint
+            .get_extension::<InterestBearingConfig>()
+            .ok()
+            .map(|config| (*config, unix_timestamp)),
+    })
+}
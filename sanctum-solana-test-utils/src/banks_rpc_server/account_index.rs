@@ -0,0 +1,90 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, RwLock},
+};
+
+use solana_program::{hash::Hash, pubkey::Pubkey};
+use solana_program_test::{BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{account::Account, signature::Keypair};
+
+use crate::ExtendedProgramTest;
+
+/// [`solana_program_test::BanksClient`] can't enumerate accounts,
+/// so the server keeps track of all the accounts it has seen.
+#[derive(Clone, Debug, Default)]
+pub struct AccountIndex(Arc<RwLock<BTreeSet<Pubkey>>>);
+
+impl AccountIndex {
+    pub fn extend(&self, pubkeys: impl IntoIterator<Item = Pubkey>) {
+        self.0.write().unwrap().extend(pubkeys);
+    }
+
+    /// All pubkeys tracked by this index, sorted
+    pub fn pubkeys(&self) -> BTreeSet<Pubkey> {
+        self.0.read().unwrap().clone()
+    }
+}
+
+/// A [`ProgramTest`] that records the pubkeys of all accounts added to it
+/// so that they can be returned by [`super::BanksRpcServer`]'s `getProgramAccounts`.
+///
+/// [`ProgramTest`] does not expose the accounts added to it, so accounts added
+/// directly to the inner [`ProgramTest`] via [`Self::program_test_mut`] are not recorded.
+#[derive(Default)]
+pub struct IndexedProgramTest {
+    pt: ProgramTest,
+    account_index: AccountIndex,
+}
+
+impl IndexedProgramTest {
+    pub fn new(pt: ProgramTest) -> Self {
+        Self {
+            pt,
+            account_index: AccountIndex::default(),
+        }
+    }
+
+    pub fn add_account(&mut self, address: Pubkey, account: Account) {
+        self.account_index.extend([address]);
+        self.pt.add_account(address, account);
+    }
+
+    /// For configuring the inner [`ProgramTest`], e.g. [`ProgramTest::add_program`].
+    /// Accounts added through this are not recorded
+    pub fn program_test_mut(&mut self) -> &mut ProgramTest {
+        &mut self.pt
+    }
+
+    pub fn account_index(&self) -> &AccountIndex {
+        &self.account_index
+    }
+
+    pub fn into_parts(self) -> (ProgramTest, AccountIndex) {
+        (self.pt, self.account_index)
+    }
+
+    /// [`ProgramTest::start`], also returning the recorded accounts
+    /// for [`super::BanksRpcServer::new_with_account_index`]
+    pub async fn start(self) -> (BanksClient, Keypair, Hash, AccountIndex) {
+        let (bc, payer, last_blockhash) = self.pt.start().await;
+        (bc, payer, last_blockhash, self.account_index)
+    }
+
+    /// [`ProgramTest::start_with_context`], also returning the recorded accounts
+    pub async fn start_with_context(self) -> (ProgramTestContext, AccountIndex) {
+        (self.pt.start_with_context().await, self.account_index)
+    }
+}
+
+impl From<ProgramTest> for IndexedProgramTest {
+    fn from(pt: ProgramTest) -> Self {
+        Self::new(pt)
+    }
+}
+
+impl ExtendedProgramTest for IndexedProgramTest {
+    fn add_account_chained(mut self, address: Pubkey, account: Account) -> Self {
+        self.add_account(address, account);
+        self
+    }
+}
@@ -0,0 +1,110 @@
+//! `sanctum_*` JSON-RPC methods for mutating the state of a [`BanksRpcServer`]
+//! created with [`BanksRpcServer::with_context`] over HTTP
+
+use std::error::Error;
+
+use solana_account_decoder::UiAccount;
+use solana_program::{clock::Clock, pubkey::Pubkey, system_program};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::account::Account;
+use tokio::sync::OwnedMutexGuard;
+
+use super::{invalid_params, json_rpc::SanctumSetClockConfig, rpc_err, BanksRpcServer};
+
+impl BanksRpcServer {
+    async fn lock_context(
+        &self,
+    ) -> Result<OwnedMutexGuard<ProgramTestContext>, Box<dyn Error + Send + Sync>> {
+        match &self.ctx {
+            Some(ctx) => Ok(ctx.clone().lock_owned().await),
+            None => Err(rpc_err(jsonrpc_core::Error {
+                code: jsonrpc_core::ErrorCode::MethodNotFound,
+                message: "Cheatcodes are only available on a BanksRpcServer created with a ProgramTestContext".to_owned(),
+                data: None,
+            })),
+        }
+    }
+
+    /// Creates or overwrites the account.
+    /// `account` must be binary encoded
+    pub async fn sanctum_set_account(
+        &mut self,
+        key: Pubkey,
+        account: UiAccount,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let account: Account = account.decode().ok_or_else(|| {
+            invalid_params("Invalid account. Supported data encodings: base58, base64, base64+zstd")
+        })?;
+        self.lock_context()
+            .await?
+            .set_account(&key, &account.into());
+        self.account_index.extend([key]);
+        Ok(())
+    }
+
+    /// Adds lamports to the account, creating a system account if it doesn't exist
+    pub async fn sanctum_airdrop(
+        &mut self,
+        key: Pubkey,
+        lamports: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut ctx = self.lock_context().await?;
+        let mut account = self
+            .bc
+            .get_account(key)
+            .await?
+            .unwrap_or_else(|| Account::new(0, 0, &system_program::ID));
+        account.lamports = account
+            .lamports
+            .checked_add(lamports)
+            .ok_or_else(|| invalid_params("lamports overflow"))?;
+        ctx.set_account(&key, &account.into());
+        self.account_index.extend([key]);
+        Ok(())
+    }
+
+    pub async fn sanctum_set_clock(
+        &mut self,
+        SanctumSetClockConfig {
+            slot,
+            epoch_start_timestamp,
+            epoch,
+            leader_schedule_epoch,
+            unix_timestamp,
+        }: SanctumSetClockConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let ctx = self.lock_context().await?;
+        let mut clock: Clock = self.bc.get_sysvar().await?;
+        clock.slot = slot.unwrap_or(clock.slot);
+        clock.epoch_start_timestamp = epoch_start_timestamp.unwrap_or(clock.epoch_start_timestamp);
+        clock.epoch = epoch.unwrap_or(clock.epoch);
+        clock.leader_schedule_epoch = leader_schedule_epoch.unwrap_or(clock.leader_schedule_epoch);
+        clock.unix_timestamp = unix_timestamp.unwrap_or(clock.unix_timestamp);
+        ctx.set_sysvar(&clock);
+        Ok(())
+    }
+
+    /// Errors if `slot` is not in the future
+    pub async fn sanctum_warp_to_slot(
+        &mut self,
+        slot: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.lock_context()
+            .await?
+            .warp_to_slot(slot)
+            .map_err(|e| invalid_params(&e.to_string()))
+    }
+
+    /// Warps to the first slot of `epoch`, then past the epoch rewards interval
+    /// so that the stake program is usable. Errors if `epoch` is not in the future
+    pub async fn sanctum_warp_to_epoch(
+        &mut self,
+        epoch: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut ctx = self.lock_context().await?;
+        ctx.warp_to_epoch(epoch)
+            .map_err(|e| invalid_params(&e.to_string()))?;
+        ctx.warp_forward_force_reward_interval_end()
+            .map_err(|e| invalid_params(&e.to_string()))
+    }
+}
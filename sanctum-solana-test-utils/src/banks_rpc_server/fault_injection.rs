@@ -0,0 +1,95 @@
+use std::{collections::HashMap, time::Duration};
+
+use super::json_rpc::RpcMethod;
+
+/// A fault injected into the response of a method of [`super::BanksRpcServer`]
+/// to simulate a flaky RPC
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Delays the HTTP response by this duration
+    Latency(Duration),
+
+    /// Responds with this HTTP status code and no JSON-RPC body,
+    /// e.g. `429 Too Many Requests` or `503 Service Unavailable`.
+    ///
+    /// Takes effect on the whole HTTP request if it's a batched one
+    HttpStatus(u16),
+
+    /// Responds with this JSON-RPC error object instead of running the method
+    RpcError(jsonrpc_core::Error),
+
+    /// Reports `context.slot` of the response this many slots behind the actual slot
+    StaleContextSlot(u64),
+
+    /// `sendTransaction` responds with the transaction's signature
+    /// without processing it, like a transaction dropped by the cluster.
+    ///
+    /// No effect on other methods
+    DropTransaction,
+}
+
+impl Fault {
+    pub const fn too_many_requests() -> Self {
+        Self::HttpStatus(429)
+    }
+
+    pub const fn service_unavailable() -> Self {
+        Self::HttpStatus(503)
+    }
+}
+
+/// A [`Fault`] that is injected into the next `remaining` requests of a method,
+/// or all of them if `remaining` is `None`
+#[derive(Clone, Debug)]
+pub struct FaultRule {
+    pub fault: Fault,
+    pub remaining: Option<u64>,
+}
+
+impl FaultRule {
+    pub const fn always(fault: Fault) -> Self {
+        Self {
+            fault,
+            remaining: None,
+        }
+    }
+
+    pub const fn times(fault: Fault, n: u64) -> Self {
+        Self {
+            fault,
+            remaining: Some(n),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FaultInjector(HashMap<RpcMethod, Vec<FaultRule>>);
+
+impl FaultInjector {
+    pub fn set(&mut self, method: RpcMethod, rules: Vec<FaultRule>) {
+        self.0.insert(method, rules);
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the faults to inject into this request of `method`,
+    /// consuming one use of each returned rule
+    pub fn draw(&mut self, method: RpcMethod) -> Vec<Fault> {
+        let rules = match self.0.get_mut(&method) {
+            Some(r) => r,
+            None => return Vec::new(),
+        };
+        rules.retain(|rule| rule.remaining != Some(0));
+        rules
+            .iter_mut()
+            .map(|rule| {
+                if let Some(remaining) = rule.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+                rule.fault.clone()
+            })
+            .collect()
+    }
+}
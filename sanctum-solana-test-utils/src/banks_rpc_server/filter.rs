@@ -0,0 +1,23 @@
+use solana_rpc_client_api::filter::{RpcFilterError, RpcFilterType};
+use spl_token_2022::{generic_token_account::GenericTokenAccount, state::Account as TokenAccount};
+
+/// Same limit as the actual RPC
+pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
+
+/// Verifies the filter and converts any encoded memcmp bytes to raw bytes
+pub fn verify_filter(mut filter: RpcFilterType) -> Result<RpcFilterType, RpcFilterError> {
+    filter.verify()?;
+    if let RpcFilterType::Memcmp(memcmp) = &mut filter {
+        memcmp.convert_to_raw_bytes()?;
+    }
+    Ok(filter)
+}
+
+/// Reimplementation of `solana_rpc::filter::filter_allows`
+pub fn filter_allows(filter: &RpcFilterType, data: &[u8]) -> bool {
+    match filter {
+        RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+        RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(data),
+        RpcFilterType::TokenAccountState => TokenAccount::valid_account_data(data),
+    }
+}
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::config::RpcAccountInfoConfig;
+
+#[derive(Deserialize)]
+struct AccountSubscribeParams(
+    #[serde(with = "As::<DisplayFromStr>")] Pubkey,
+    #[serde(default)] Option<RpcAccountInfoConfig>,
+);
+
+pub fn deser_account_subscribe_params(
+    params: Value,
+) -> Result<(Pubkey, Option<RpcAccountInfoConfig>), serde_json::Error> {
+    let AccountSubscribeParams(key, cfg) = serde_json::from_value(params)?;
+    Ok((key, cfg))
+}
@@ -7,7 +7,7 @@ use solana_rpc_client_api::config::RpcAccountInfoConfig;
 #[derive(Deserialize)]
 struct GetAccountInfoParams(
     #[serde(with = "As::<DisplayFromStr>")] Pubkey,
-    Option<RpcAccountInfoConfig>,
+    #[serde(default)] Option<RpcAccountInfoConfig>,
 );
 
 pub fn deser_get_account_info_params(
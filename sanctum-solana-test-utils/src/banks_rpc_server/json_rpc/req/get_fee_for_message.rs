@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use serde_json::Value;
+use solana_rpc_client_api::config::RpcContextConfig;
+
+/// The message is base64 encoded
+#[derive(Deserialize)]
+struct GetFeeForMessageParams(String, #[serde(default)] Option<RpcContextConfig>);
+
+pub fn deser_get_fee_for_message_params(
+    params: Value,
+) -> Result<(String, Option<RpcContextConfig>), serde_json::Error> {
+    let GetFeeForMessageParams(encoded_message, cfg) = serde_json::from_value(params)?;
+    Ok((encoded_message, cfg))
+}
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+#[derive(Deserialize)]
+struct GetMinimumBalanceForRentExemptionParams(usize, #[serde(default)] Option<CommitmentConfig>);
+
+pub fn deser_get_minimum_balance_for_rent_exemption_params(
+    params: Value,
+) -> Result<(usize, Option<CommitmentConfig>), serde_json::Error> {
+    let GetMinimumBalanceForRentExemptionParams(data_len, cfg) = serde_json::from_value(params)?;
+    Ok((data_len, cfg))
+}
@@ -7,7 +7,7 @@ use solana_rpc_client_api::config::RpcAccountInfoConfig;
 #[derive(Deserialize)]
 struct GetMultipleAccountsParams(
     #[serde(with = "As::<Vec<DisplayFromStr>>")] Vec<Pubkey>,
-    Option<RpcAccountInfoConfig>,
+    #[serde(default)] Option<RpcAccountInfoConfig>,
 );
 
 pub fn deser_get_multiple_accounts_params(
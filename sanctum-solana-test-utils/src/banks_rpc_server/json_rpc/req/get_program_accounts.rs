@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::config::RpcProgramAccountsConfig;
+
+#[derive(Deserialize)]
+struct GetProgramAccountsParams(
+    #[serde(with = "As::<DisplayFromStr>")] Pubkey,
+    #[serde(default)] Option<RpcProgramAccountsConfig>,
+);
+
+pub fn deser_get_program_accounts_params(
+    params: Value,
+) -> Result<(Pubkey, Option<RpcProgramAccountsConfig>), serde_json::Error> {
+    let GetProgramAccountsParams(program_id, cfg) = serde_json::from_value(params)?;
+    Ok((program_id, cfg))
+}
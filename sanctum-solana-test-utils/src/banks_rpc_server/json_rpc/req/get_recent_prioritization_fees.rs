@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Deserialize)]
+struct Addresses(#[serde(with = "As::<Vec<DisplayFromStr>>")] Vec<Pubkey>);
+
+pub fn deser_get_recent_prioritization_fees_params(
+    params: Value,
+) -> Result<Vec<Pubkey>, serde_json::Error> {
+    let (addresses,): (Option<Addresses>,) = serde_json::from_value(params)?;
+    Ok(addresses.map_or_else(Vec::new, |Addresses(a)| a))
+}
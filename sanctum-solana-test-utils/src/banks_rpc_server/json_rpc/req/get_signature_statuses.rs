@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_rpc_client_api::config::RpcSignatureStatusConfig;
+use solana_sdk::signature::Signature;
+
+#[derive(Deserialize)]
+struct GetSignatureStatusesParams(
+    #[serde(with = "As::<Vec<DisplayFromStr>>")] Vec<Signature>,
+    #[serde(default)] Option<RpcSignatureStatusConfig>,
+);
+
+pub fn deser_get_signature_statuses_params(
+    params: Value,
+) -> Result<(Vec<Signature>, Option<RpcSignatureStatusConfig>), serde_json::Error> {
+    let GetSignatureStatusesParams(signatures, cfg) = serde_json::from_value(params)?;
+    Ok((signatures, cfg))
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::{config::RpcAccountInfoConfig, request::TokenAccountsFilter};
+
+/// Same as `solana_rpc_client_api::config::RpcTokenAccountsFilter`,
+/// but with the pubkeys deserialized
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum TokenAccountsFilterParam {
+    Mint(#[serde(with = "As::<DisplayFromStr>")] Pubkey),
+    ProgramId(#[serde(with = "As::<DisplayFromStr>")] Pubkey),
+}
+
+impl From<TokenAccountsFilterParam> for TokenAccountsFilter {
+    fn from(value: TokenAccountsFilterParam) -> Self {
+        match value {
+            TokenAccountsFilterParam::Mint(mint) => Self::Mint(mint),
+            TokenAccountsFilterParam::ProgramId(program_id) => Self::ProgramId(program_id),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GetTokenAccountsByOwnerParams(
+    #[serde(with = "As::<DisplayFromStr>")] Pubkey,
+    TokenAccountsFilterParam,
+    #[serde(default)] Option<RpcAccountInfoConfig>,
+);
+
+pub fn deser_get_token_accounts_by_owner_params(
+    params: Value,
+) -> Result<(Pubkey, TokenAccountsFilter, Option<RpcAccountInfoConfig>), serde_json::Error> {
+    let GetTokenAccountsByOwnerParams(owner, filter, cfg) = serde_json::from_value(params)?;
+    Ok((owner, filter.into(), cfg))
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_rpc_client_api::config::{RpcEncodingConfigWrapper, RpcTransactionConfig};
+use solana_sdk::signature::Signature;
+
+/// `RpcClient::get_transaction()` passes the encoding directly as the 2nd param
+/// instead of a `RpcTransactionConfig`
+#[derive(Deserialize)]
+struct GetTransactionParams(
+    #[serde(with = "As::<DisplayFromStr>")] Signature,
+    #[serde(default)] Option<RpcEncodingConfigWrapper<RpcTransactionConfig>>,
+);
+
+pub fn deser_get_transaction_params(
+    params: Value,
+) -> Result<(Signature, Option<RpcTransactionConfig>), serde_json::Error> {
+    let GetTransactionParams(signature, cfg) = serde_json::from_value(params)?;
+    Ok((signature, cfg.map(|c| c.convert_to_current())))
+}
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::hash::Hash;
+use solana_rpc_client_api::config::RpcContextConfig;
+
+#[derive(Deserialize)]
+struct IsBlockhashValidParams(
+    #[serde(with = "As::<DisplayFromStr>")] Hash,
+    #[serde(default)] Option<RpcContextConfig>,
+);
+
+pub fn deser_is_blockhash_valid_params(
+    params: Value,
+) -> Result<(Hash, Option<RpcContextConfig>), serde_json::Error> {
+    let IsBlockhashValidParams(blockhash, cfg) = serde_json::from_value(params)?;
+    Ok((blockhash, cfg))
+}
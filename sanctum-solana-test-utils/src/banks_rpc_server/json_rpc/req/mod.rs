@@ -3,25 +3,96 @@ use serde_json::Value;
 
 use super::JsonRpc2Ident;
 
+mod account_subscribe;
 mod get_account_info;
+mod get_balance;
+mod get_epoch_info;
+mod get_fee_for_message;
 mod get_latest_blockhash;
+mod get_minimum_balance_for_rent_exemption;
 mod get_multiple_accounts;
+mod get_program_accounts;
+mod get_recent_prioritization_fees;
+mod get_signature_statuses;
+mod get_slot;
+mod get_token_account_balance;
+mod get_token_accounts_by_owner;
+mod get_transaction;
+mod is_blockhash_valid;
+mod sanctum_airdrop;
+mod sanctum_set_account;
+mod sanctum_set_clock;
+mod sanctum_warp_to_epoch;
+mod sanctum_warp_to_slot;
+mod send_transaction;
+mod signature_subscribe;
+mod simulate_transaction;
+mod unsubscribe;
 
+pub use account_subscribe::*;
 pub use get_account_info::*;
+pub use get_balance::*;
+pub use get_epoch_info::*;
+pub use get_fee_for_message::*;
 pub use get_latest_blockhash::*;
+pub use get_minimum_balance_for_rent_exemption::*;
 pub use get_multiple_accounts::*;
+pub use get_program_accounts::*;
+pub use get_recent_prioritization_fees::*;
+pub use get_signature_statuses::*;
+pub use get_slot::*;
+pub use get_token_account_balance::*;
+pub use get_token_accounts_by_owner::*;
+pub use get_transaction::*;
+pub use is_blockhash_valid::*;
+pub use sanctum_airdrop::*;
+pub use sanctum_set_account::*;
+pub use sanctum_set_clock::*;
+pub use sanctum_warp_to_epoch::*;
+pub use sanctum_warp_to_slot::*;
+pub use send_transaction::*;
+pub use signature_subscribe::*;
+pub use simulate_transaction::*;
+pub use unsubscribe::*;
 
 // TODO: other methods
 /// solana_rpc_client_api::request::RpcRequest doesn't implement Serialize or Deserialize, or TryFromStr to use with #[serde(with = "As::<DisplayFromStr>")],
 /// so we're redefining it here
 #[allow(clippy::enum_variant_names)] // common "Get" prefix
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcMethod {
     GetAccountInfo,
+    GetBalance,
+    GetEpochInfo,
+    GetFeeForMessage,
     GetLatestBlockhash,
+    GetMinimumBalanceForRentExemption,
     GetMultipleAccounts,
+    GetProgramAccounts,
+    GetRecentPrioritizationFees,
+    GetSignatureStatuses,
+    GetSlot,
+    GetTokenAccountBalance,
+    GetTokenAccountsByOwner,
+    GetTransaction,
     GetVersion, // many RpcClient methods call this method before calling the actual method
+    IsBlockhashValid,
+
+    // cheatcodes, only available with a `ProgramTestContext`
+    #[serde(rename = "sanctum_airdrop")]
+    SanctumAirdrop,
+    #[serde(rename = "sanctum_setAccount")]
+    SanctumSetAccount,
+    #[serde(rename = "sanctum_setClock")]
+    SanctumSetClock,
+    #[serde(rename = "sanctum_warpToEpoch")]
+    SanctumWarpToEpoch,
+    #[serde(rename = "sanctum_warpToSlot")]
+    SanctumWarpToSlot,
+
+    SendTransaction,
+    SimulateTransaction,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -31,3 +102,24 @@ pub struct JsonRpcReq {
     pub method: RpcMethod,
     pub params: Value,
 }
+
+/// Methods served over the websocket pubsub endpoint
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PubsubMethod {
+    AccountSubscribe,
+    AccountUnsubscribe,
+    SignatureSubscribe,
+    SignatureUnsubscribe,
+    SlotSubscribe,
+    SlotUnsubscribe,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonRpcPubsubReq {
+    pub jsonrpc: JsonRpc2Ident,
+    pub id: u64,
+    pub method: PubsubMethod,
+    #[serde(default)] // slotSubscribe has no params
+    pub params: Value,
+}
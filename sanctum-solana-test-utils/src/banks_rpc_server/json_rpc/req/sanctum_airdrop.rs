@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Deserialize)]
+struct SanctumAirdropParams(#[serde(with = "As::<DisplayFromStr>")] Pubkey, u64);
+
+pub fn deser_sanctum_airdrop_params(params: Value) -> Result<(Pubkey, u64), serde_json::Error> {
+    let SanctumAirdropParams(key, lamports) = serde_json::from_value(params)?;
+    Ok((key, lamports))
+}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_account_decoder::UiAccount;
+use solana_program::pubkey::Pubkey;
+
+/// The account is in the same format as the `value` of a `getAccountInfo` response
+#[derive(Deserialize)]
+struct SanctumSetAccountParams(#[serde(with = "As::<DisplayFromStr>")] Pubkey, UiAccount);
+
+pub fn deser_sanctum_set_account_params(
+    params: Value,
+) -> Result<(Pubkey, UiAccount), serde_json::Error> {
+    let SanctumSetAccountParams(key, account) = serde_json::from_value(params)?;
+    Ok((key, account))
+}
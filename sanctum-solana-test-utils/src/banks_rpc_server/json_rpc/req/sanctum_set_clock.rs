@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Fields of the `Clock` sysvar to overwrite, unset fields are left unchanged
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanctumSetClockConfig {
+    pub slot: Option<u64>,
+    pub epoch_start_timestamp: Option<i64>,
+    pub epoch: Option<u64>,
+    pub leader_schedule_epoch: Option<u64>,
+    pub unix_timestamp: Option<i64>,
+}
+
+pub fn deser_sanctum_set_clock_params(
+    params: Value,
+) -> Result<SanctumSetClockConfig, serde_json::Error> {
+    let (res,) = serde_json::from_value(params)?;
+    Ok(res)
+}
@@ -0,0 +1,6 @@
+use serde_json::Value;
+
+pub fn deser_sanctum_warp_to_epoch_params(params: Value) -> Result<u64, serde_json::Error> {
+    let (res,) = serde_json::from_value(params)?;
+    Ok(res)
+}
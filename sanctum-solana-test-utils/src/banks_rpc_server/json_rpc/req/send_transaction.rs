@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use serde_json::Value;
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+
+#[derive(Deserialize)]
+struct SendTransactionParams(String, #[serde(default)] Option<RpcSendTransactionConfig>);
+
+/// Returns `(encoded_tx, cfg)`
+pub fn deser_send_transaction_params(
+    params: Value,
+) -> Result<(String, Option<RpcSendTransactionConfig>), serde_json::Error> {
+    let SendTransactionParams(encoded_tx, cfg) = serde_json::from_value(params)?;
+    Ok((encoded_tx, cfg))
+}
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use serde_json::Value;
+use serde_with::{As, DisplayFromStr};
+use solana_rpc_client_api::config::RpcSignatureSubscribeConfig;
+use solana_sdk::signature::Signature;
+
+#[derive(Deserialize)]
+struct SignatureSubscribeParams(
+    #[serde(with = "As::<DisplayFromStr>")] Signature,
+    #[serde(default)] Option<RpcSignatureSubscribeConfig>,
+);
+
+pub fn deser_signature_subscribe_params(
+    params: Value,
+) -> Result<(Signature, Option<RpcSignatureSubscribeConfig>), serde_json::Error> {
+    let SignatureSubscribeParams(signature, cfg) = serde_json::from_value(params)?;
+    Ok((signature, cfg))
+}
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use serde_json::Value;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+
+#[derive(Deserialize)]
+struct SimulateTransactionParams(
+    String,
+    #[serde(default)] Option<RpcSimulateTransactionConfig>,
+);
+
+/// Returns `(encoded_tx, cfg)`
+pub fn deser_simulate_transaction_params(
+    params: Value,
+) -> Result<(String, Option<RpcSimulateTransactionConfig>), serde_json::Error> {
+    let SimulateTransactionParams(encoded_tx, cfg) = serde_json::from_value(params)?;
+    Ok((encoded_tx, cfg))
+}
@@ -0,0 +1,7 @@
+use serde_json::Value;
+
+/// Params of all the `*Unsubscribe` pubsub methods: `[subscription_id]`
+pub fn deser_unsubscribe_params(params: Value) -> Result<u64, serde_json::Error> {
+    let (res,) = serde_json::from_value(params)?;
+    Ok(res)
+}
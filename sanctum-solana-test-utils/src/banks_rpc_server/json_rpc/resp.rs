@@ -1,5 +1,5 @@
 use http_body_util::Full;
-use hyper::body::Bytes;
+use hyper::{body::Bytes, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use solana_rpc_client_api::response::{Response, RpcResponseContext};
@@ -14,6 +14,15 @@ pub fn to_http_resp(data: Bytes) -> hyper::Response<Full<Bytes>> {
         .unwrap()
 }
 
+/// Invalid status codes are responded to with 500
+pub fn to_http_status_resp(status: u16) -> hyper::Response<Full<Bytes>> {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    hyper::Response::builder()
+        .status(status)
+        .body(Bytes::from(status.canonical_reason().unwrap_or_default()).into())
+        .unwrap()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JsonRpcResp<T> {
     pub jsonrpc: JsonRpc2Ident,
@@ -49,3 +58,72 @@ impl<T: Serialize> From<JsonRpcResp<T>> for Value {
         serde_json::to_value(value).unwrap()
     }
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonRpcErrResp {
+    pub jsonrpc: JsonRpc2Ident,
+    pub id: u64,
+    pub error: jsonrpc_core::Error,
+}
+
+impl JsonRpcErrResp {
+    pub fn new(id: u64, error: jsonrpc_core::Error) -> Self {
+        Self {
+            jsonrpc: Default::default(),
+            id,
+            error,
+        }
+    }
+}
+
+impl From<JsonRpcErrResp> for Value {
+    fn from(value: JsonRpcErrResp) -> Self {
+        serde_json::to_value(value).unwrap()
+    }
+}
+
+/// Server-initiated pubsub notification, e.g. `signatureNotification`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonRpcNotification<T> {
+    pub jsonrpc: JsonRpc2Ident,
+    pub method: String,
+    pub params: JsonRpcNotificationParams<T>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonRpcNotificationParams<T> {
+    pub result: T,
+    pub subscription: u64,
+}
+
+impl<T> JsonRpcNotification<T> {
+    pub fn new(method: &str, subscription: u64, result: T) -> Self {
+        Self {
+            jsonrpc: Default::default(),
+            method: method.to_owned(),
+            params: JsonRpcNotificationParams {
+                result,
+                subscription,
+            },
+        }
+    }
+}
+
+impl<T> JsonRpcNotification<Response<T>> {
+    pub fn with_ctx(method: &str, subscription: u64, value: T, slot: u64) -> Self {
+        Self::new(
+            method,
+            subscription,
+            Response {
+                context: RpcResponseContext::new(slot),
+                value,
+            },
+        )
+    }
+}
+
+impl<T: Serialize> From<JsonRpcNotification<T>> for Value {
+    fn from(value: JsonRpcNotification<T>) -> Self {
+        serde_json::to_value(value).unwrap()
+    }
+}
@@ -1,6 +1,7 @@
 //! A http server that runs solana RPC requests against a `BanksClient`
 //! so that you can for e.g. run CLI integration tests against `ProgramTest` instead of an actual cluster
 
+use data_encoding::BASE64;
 use http_body_util::{BodyExt, Full};
 use hyper::{
     body::{Buf, Bytes, Incoming},
@@ -10,26 +11,115 @@ use hyper::{
 };
 use hyper_util::rt::TokioIo;
 use serde_json::Value;
-use solana_account_decoder::{UiAccount, UiAccountEncoding, UiDataSliceConfig};
-use solana_program::{clock::Clock, pubkey::Pubkey};
-use solana_program_test::BanksClient;
+use solana_account_decoder::{
+    parse_account_data::{AccountAdditionalDataV2, SplTokenAdditionalData},
+    parse_token::{
+        get_token_account_mint, is_known_spl_token_id, token_amount_to_ui_amount_v2, UiTokenAmount,
+    },
+    UiAccount, UiAccountEncoding, UiDataSliceConfig,
+};
+use solana_program::{
+    address_lookup_table::state::AddressLookupTable,
+    clock::Clock,
+    epoch_schedule::EpochSchedule,
+    hash::Hash,
+    instruction::CompiledInstruction,
+    message::{
+        v0::{LoadedAddresses, MessageAddressTableLookup},
+        Message, MessageHeader, VersionedMessage,
+    },
+    pubkey::Pubkey,
+};
+use solana_program_test::{BanksClient, BanksTransactionResultWithMetadata, ProgramTestContext};
 use solana_rpc_client_api::{
-    config::RpcAccountInfoConfig,
-    response::{RpcBlockhash, RpcVersionInfo},
+    config::{
+        RpcAccountInfoConfig, RpcContextConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcSignatureStatusConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+    },
+    custom_error::RpcCustomError,
+    filter::RpcFilterType,
+    request::{TokenAccountsFilter, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS},
+    response::{
+        OptionalContext, Response as RpcResponse, RpcBlockhash, RpcKeyedAccount,
+        RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult, RpcVersionInfo,
+    },
+};
+use solana_sdk::{
+    account::Account,
+    bs58,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    epoch_info::EpochInfo,
+    message::AccountKeys,
+    signature::Signature,
+    transaction::{VersionedTransaction, MAX_TX_ACCOUNT_LOCKS},
+};
+use solana_transaction_status::{
+    map_inner_instructions, ConfirmedTransactionWithStatusMeta,
+    EncodedConfirmedTransactionWithStatusMeta, TransactionBinaryEncoding,
+    TransactionConfirmationStatus, TransactionStatus, TransactionStatusMeta,
+    TransactionWithStatusMeta, UiInnerInstructions, UiTransactionEncoding,
+    VersionedConfirmedTransactionWithStatusMeta, VersionedTransactionWithStatusMeta,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions, generic_token_account::GenericTokenAccount,
+    state::Account as TokenAccount,
 };
-use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
-use std::{cmp, error::Error, future::Future, pin::Pin};
-use tokio::{net::TcpListener, task::JoinHandle};
+use std::{
+    collections::HashMap,
+    error::Error,
+    future::Future,
+    io,
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+};
+use tokio::{net::TcpListener, sync::broadcast, task::JoinHandle};
 
 use crate::banks_rpc_server::json_rpc::{
-    deser_get_multiple_accounts_params, JsonRpcReq, JsonRpcResp, RpcMethod,
+    deser_get_multiple_accounts_params, JsonRpcReq, JsonRpcResp,
 };
 
 use self::json_rpc::{
-    deser_get_account_info_params, deser_get_latest_blockhash_params, to_http_resp,
+    deser_get_account_info_params, deser_get_balance_params, deser_get_epoch_info_params,
+    deser_get_fee_for_message_params, deser_get_latest_blockhash_params,
+    deser_get_minimum_balance_for_rent_exemption_params, deser_get_program_accounts_params,
+    deser_get_recent_prioritization_fees_params, deser_get_signature_statuses_params,
+    deser_get_slot_params, deser_get_token_account_balance_params,
+    deser_get_token_accounts_by_owner_params, deser_get_transaction_params,
+    deser_is_blockhash_valid_params, deser_sanctum_airdrop_params,
+    deser_sanctum_set_account_params, deser_sanctum_set_clock_params,
+    deser_sanctum_warp_to_epoch_params, deser_sanctum_warp_to_slot_params,
+    deser_send_transaction_params, deser_simulate_transaction_params, to_http_resp,
+    to_http_status_resp, JsonRpcErrResp,
+};
+
+use self::{
+    account_encoding::{encode_account, spl_token_additional_data},
+    fault_injection::FaultInjector,
+    filter::{filter_allows, verify_filter, MAX_GET_PROGRAM_ACCOUNT_FILTERS},
+    pubsub::{ProcessedTxEvent, PROCESSED_TX_EVENTS_CAPACITY},
+    recording::RpcRecorder,
 };
 
+mod account_encoding;
+mod account_index;
+mod cheatcodes;
+mod fault_injection;
+mod filter;
 mod json_rpc;
+mod prioritization_fee_source;
+mod pubsub;
+mod recording;
+mod replay;
+
+pub use fault_injection::{Fault, FaultRule};
+pub use json_rpc::{RpcMethod, SanctumSetClockConfig};
+pub use prioritization_fee_source::*;
+pub use pubsub::SpawnedServers;
+pub use recording::{read_recording, RecordedExchange};
+pub use replay::*;
+
+pub use account_index::{AccountIndex, IndexedProgramTest};
 
 /// A simulated solana RPC server backed by a [`BanksClient`]
 ///
@@ -46,30 +136,230 @@ pub struct BanksRpcServer {
     // TODO: change this to BanksServer when solana makes it easier
     // to construct them from ProgramTest
     bc: BanksClient,
+    processed_txs: Arc<RwLock<HashMap<Signature, VersionedConfirmedTransactionWithStatusMeta>>>,
+    account_index: AccountIndex,
+    prioritization_fee_source: Arc<RwLock<PrioritizationFeeSource>>,
+    processed_tx_events: broadcast::Sender<ProcessedTxEvent>,
+    faults: Arc<RwLock<FaultInjector>>,
+    recorder: Arc<Mutex<Option<RpcRecorder>>>,
+    ctx: Option<Arc<tokio::sync::Mutex<ProgramTestContext>>>,
+}
+
+/// Errors returned as JSON-RPC error objects in the response instead of failing the request
+fn rpc_err(e: impl Into<jsonrpc_core::Error>) -> Box<dyn Error + Send + Sync> {
+    Box::new(e.into())
+}
+
+fn invalid_params(message: &str) -> Box<dyn Error + Send + Sync> {
+    rpc_err(jsonrpc_core::Error::invalid_params(message))
+}
+
+/// Decodes the transaction param of `sendTransaction` and `simulateTransaction`.
+/// Encoding defaults to base58, same as the actual RPC.
+fn decode_tx(
+    encoded_tx: &str,
+    encoding: Option<UiTransactionEncoding>,
+) -> Result<VersionedTransaction, jsonrpc_core::Error> {
+    let encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+    let bytes = match encoding.into_binary_encoding() {
+        Some(TransactionBinaryEncoding::Base58) => {
+            bs58::decode(encoded_tx).into_vec().map_err(|e| {
+                jsonrpc_core::Error::invalid_params(format!("invalid base58 encoding: {e}"))
+            })?
+        }
+        Some(TransactionBinaryEncoding::Base64) => {
+            BASE64.decode(encoded_tx.as_bytes()).map_err(|e| {
+                jsonrpc_core::Error::invalid_params(format!("invalid base64 encoding: {e}"))
+            })?
+        }
+        None => {
+            return Err(jsonrpc_core::Error::invalid_params(format!(
+                "unsupported encoding: {encoding}. Supported encodings: base58, base64"
+            )))
+        }
+    };
+    let tx: VersionedTransaction = bincode::deserialize(&bytes).map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!(
+            "failed to deserialize VersionedTransaction: {e}"
+        ))
+    })?;
+    tx.sanitize()
+        .map_err(|e| jsonrpc_core::Error::invalid_params(format!("invalid transaction: {e}")))?;
+    Ok(tx)
+}
+
+/// Decodes the base64 encoded message param of `getFeeForMessage`
+fn decode_message(encoded_message: &str) -> Result<VersionedMessage, jsonrpc_core::Error> {
+    let bytes = BASE64.decode(encoded_message.as_bytes()).map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!("invalid base64 encoding: {e}"))
+    })?;
+    let message: VersionedMessage = bincode::deserialize(&bytes).map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!("failed to deserialize VersionedMessage: {e}"))
+    })?;
+    message.sanitize().map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!("invalid transaction message: {e}"))
+    })?;
+    Ok(message)
 }
 
-fn account_data_sliced(mut account: Account, ds: Option<UiDataSliceConfig>) -> Account {
-    let UiDataSliceConfig { offset, length } = match ds {
-        Some(ds) => ds,
-        None => return account,
+fn is_sig_verified(tx: &VersionedTransaction) -> bool {
+    tx.verify_with_results()
+        .into_iter()
+        .all(|verified| verified)
+}
+
+/// [`BanksClient::get_fee_for_message`] only accepts legacy messages.
+///
+/// Converts a v0 message to its legacy equivalent by inlining the loaded addresses,
+/// moving the loaded writable keys in front of the static readonly non-signer keys
+/// and remapping the instructions' account indices accordingly
+fn to_legacy_message(message: &VersionedMessage, loaded_addresses: &LoadedAddresses) -> Message {
+    let v0 = match message {
+        VersionedMessage::Legacy(m) => return m.clone(),
+        VersionedMessage::V0(m) => m,
+    };
+    let n_static = v0.account_keys.len();
+    let n_static_writable = n_static - usize::from(v0.header.num_readonly_unsigned_accounts);
+    let n_loaded_writable = loaded_addresses.writable.len();
+    let remap = |i: u8| -> u8 {
+        let i = usize::from(i);
+        let new_i = if i < n_static_writable {
+            i
+        } else if i < n_static {
+            i + n_loaded_writable
+        } else if i < n_static + n_loaded_writable {
+            i - (n_static - n_static_writable)
+        } else {
+            i
+        };
+        // legacy messages have the same max number of keys
+        new_i.try_into().unwrap()
     };
-    if offset >= account.data.len() {
-        account.data = Vec::new();
-        return account;
+    let account_keys = v0.account_keys[..n_static_writable]
+        .iter()
+        .chain(loaded_addresses.writable.iter())
+        .chain(v0.account_keys[n_static_writable..].iter())
+        .chain(loaded_addresses.readonly.iter())
+        .copied()
+        .collect();
+    let instructions = v0
+        .instructions
+        .iter()
+        .map(|ix| CompiledInstruction {
+            program_id_index: remap(ix.program_id_index),
+            accounts: ix.accounts.iter().copied().map(remap).collect(),
+            data: ix.data.clone(),
+        })
+        .collect();
+    Message {
+        header: MessageHeader {
+            num_readonly_unsigned_accounts: v0.header.num_readonly_unsigned_accounts
+                + u8::try_from(loaded_addresses.readonly.len()).unwrap(),
+            ..v0.header
+        },
+        account_keys,
+        recent_blockhash: v0.recent_blockhash,
+        instructions,
     }
-    let end = cmp::min(offset + length, account.data.len());
-    account.data = account.data.drain(offset..end).collect();
-    account
 }
 
 impl BanksRpcServer {
+    pub fn new(bc: BanksClient) -> Self {
+        Self::new_with_account_index(bc, AccountIndex::default())
+    }
+
+    /// `getProgramAccounts` and `getTokenAccountsByOwner` will return
+    /// the accounts in `account_index` that exist,
+    /// e.g. the accounts added to an [`IndexedProgramTest`]
+    pub fn new_with_account_index(bc: BanksClient, account_index: AccountIndex) -> Self {
+        Self {
+            bc,
+            processed_txs: Default::default(),
+            account_index,
+            prioritization_fee_source: Default::default(),
+            processed_tx_events: broadcast::channel(PROCESSED_TX_EVENTS_CAPACITY).0,
+            faults: Default::default(),
+            recorder: Default::default(),
+            ctx: None,
+        }
+    }
+
+    /// Enables the `sanctum_*` cheatcode methods for mutating state over HTTP,
+    /// e.g. `sanctum_setAccount` and `sanctum_warpToSlot`.
+    ///
+    /// Use [`Self::program_test_context`] to access the context after this.
+    pub fn with_context(ctx: ProgramTestContext) -> Self {
+        let bc = ctx.banks_client.clone();
+        Self {
+            ctx: Some(Arc::new(tokio::sync::Mutex::new(ctx))),
+            ..Self::new(bc)
+        }
+    }
+
+    /// Shared with clones of this server, `None` if not created with [`Self::with_context`]
+    pub fn program_test_context(&self) -> Option<Arc<tokio::sync::Mutex<ProgramTestContext>>> {
+        self.ctx.clone()
+    }
+
+    /// Sets where `getRecentPrioritizationFees` responses come from.
+    ///
+    /// Clones of this server share the same source,
+    /// so this can still be called on a clone after the server is spawned.
+    pub fn set_prioritization_fee_source(&self, source: PrioritizationFeeSource) {
+        *self.prioritization_fee_source.write().unwrap() = source;
+    }
+
+    /// Replaces the faults injected into responses of `method`, see [`Fault`].
+    ///
+    /// Multiple rules can apply to the same request, e.g. latency followed by an error.
+    /// Clones of this server share the same faults,
+    /// so this can still be called on a clone after the server is spawned.
+    pub fn set_faults(&self, method: RpcMethod, rules: impl IntoIterator<Item = FaultRule>) {
+        self.faults
+            .write()
+            .unwrap()
+            .set(method, rules.into_iter().collect());
+    }
+
+    /// Removes all faults set via [`Self::set_faults`]
+    pub fn clear_faults(&self) {
+        self.faults.write().unwrap().clear();
+    }
+
+    /// Writes every subsequent JSON-RPC request and its response to a JSONL file at `path`,
+    /// one [`RecordedExchange`] per line, for replaying with [`RpcReplayServer`].
+    ///
+    /// Truncates the file if it already exists.
+    /// Clones of this server share the same recording,
+    /// so this can still be called on a clone after the server is spawned.
+    pub fn start_recording(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        *self.recorder.lock().unwrap() = Some(RpcRecorder::create(path)?);
+        Ok(())
+    }
+
+    pub fn stop_recording(&self) {
+        *self.recorder.lock().unwrap() = None;
+    }
+
+    /// Registers accounts to be returned by `getProgramAccounts`.
+    ///
+    /// Accounts in the [`AccountIndex`] passed to [`Self::new_with_account_index`]
+    /// and accounts referenced by transactions processed by this server are already registered.
+    /// Accounts written by transactions sent directly to the [`BanksClient`] are not.
+    ///
+    /// Clones of this server share the same registered accounts,
+    /// so this can still be called on a clone after the server is spawned.
+    pub fn register_accounts(&self, pubkeys: impl IntoIterator<Item = Pubkey>) {
+        self.account_index.extend(pubkeys);
+    }
+
     /// Spawns the HTTP server on `http://127.0.0.1:{random_unused_port}` (IPV4).
     ///
     /// Returns `(bound_port, BanksRpcServer join handle)`
     pub async fn spawn_random_unused(
         bc: BanksClient,
     ) -> (u16, JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>) {
-        let s = Self { bc };
+        let s = Self::new(bc);
         let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
         let port = tcp_listener.local_addr().unwrap().port();
         (port, s.spawn(tcp_listener))
@@ -102,22 +392,85 @@ impl BanksRpcServer {
         slot
     }
 
-    // TODO: handle cfg encoding, commitment, min_context_slot.
-    // This just returns base64 encoded for now
+    /// Errors with [`RpcCustomError::MinContextSlotNotReached`]
+    /// if the current slot is below `min_context_slot`
+    pub async fn check_min_context_slot(
+        &mut self,
+        min_context_slot: Option<u64>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let context_slot = self.curr_slot().await;
+        match min_context_slot {
+            Some(min_context_slot) if context_slot < min_context_slot => {
+                Err(rpc_err(RpcCustomError::MinContextSlotNotReached {
+                    context_slot,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Encodes the account the same way the actual RPC does.
+    ///
+    /// `jsonParsed` token accounts are parsed with their mint's decimals
+    /// and `data_slice` is ignored for them
+    pub async fn encode_account(
+        &mut self,
+        key: &Pubkey,
+        account: Account,
+        encoding: UiAccountEncoding,
+        data_slice: Option<UiDataSliceConfig>,
+    ) -> Result<UiAccount, Box<dyn Error + Send + Sync>> {
+        if encoding != UiAccountEncoding::JsonParsed || !is_known_spl_token_id(&account.owner) {
+            return encode_account(key, &account, encoding, data_slice).map_err(rpc_err);
+        }
+        let spl_token_additional_data = match get_token_account_mint(&account.data) {
+            Some(mint) => match self.bc.get_account(mint).await? {
+                Some(mint_account) => {
+                    let Clock { unix_timestamp, .. } = self.bc.get_sysvar().await?;
+                    spl_token_additional_data(&mint_account.data, unix_timestamp)
+                }
+                None => None,
+            },
+            None => None,
+        };
+        Ok(UiAccount::encode(
+            key,
+            &account,
+            encoding,
+            spl_token_additional_data.map(|data| AccountAdditionalDataV2 {
+                spl_token_additional_data: Some(data),
+            }),
+            None,
+        ))
+    }
+
+    /// All commitment levels point to the same bank in `ProgramTest`
     pub async fn get_account_info(
         &mut self,
         key: Pubkey,
         cfg: Option<RpcAccountInfoConfig>,
     ) -> Result<Option<UiAccount>, Box<dyn Error + Send + Sync>> {
-        Ok(self.bc.get_account(key).await?.map(|account| {
-            UiAccount::encode(
-                &key,
-                &account_data_sliced(account, cfg.map_or_else(|| None, |c| c.data_slice)),
-                UiAccountEncoding::Base64,
-                None,
-                None,
-            )
-        }))
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            commitment,
+            min_context_slot,
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let account = match self
+            .bc
+            .get_account_with_commitment(key, commitment.unwrap_or_default().commitment)
+            .await?
+        {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+        // same default encoding as the actual RPC
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        Ok(Some(
+            self.encode_account(&key, account, encoding, data_slice)
+                .await?,
+        ))
     }
 
     pub async fn get_latest_blockhash(&mut self, cfg: Option<CommitmentConfig>) -> RpcBlockhash {
@@ -133,25 +486,668 @@ impl BanksRpcServer {
         }
     }
 
-    // TODO: handle cfg encoding, commitment, min_context_slot.
-    // This just returns base64 encoded for now
+    /// All commitment levels point to the same bank in `ProgramTest`
     pub async fn get_multiple_accounts(
         &mut self,
         keys: Vec<Pubkey>,
         cfg: Option<RpcAccountInfoConfig>,
     ) -> Result<Vec<Option<UiAccount>>, Box<dyn Error + Send + Sync>> {
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            commitment,
+            min_context_slot,
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let commitment = commitment.unwrap_or_default().commitment;
+        // same default encoding as the actual RPC
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Base64);
+        let mut res = Vec::with_capacity(keys.len());
+        for key in keys {
+            let account = match self.bc.get_account_with_commitment(key, commitment).await? {
+                Some(a) => a,
+                None => {
+                    res.push(None);
+                    continue;
+                }
+            };
+            res.push(Some(
+                self.encode_account(&key, account, encoding, data_slice)
+                    .await?,
+            ));
+        }
+        Ok(res)
+    }
+
+    /// [`BanksClient`] can't enumerate accounts, so this only returns the accounts
+    /// the server knows about, see [`Self::register_accounts`]
+    pub async fn get_program_accounts(
+        &mut self,
+        program_id: Pubkey,
+        cfg: Option<RpcProgramAccountsConfig>,
+    ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>, Box<dyn Error + Send + Sync>> {
+        let RpcProgramAccountsConfig {
+            filters,
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    commitment,
+                    min_context_slot,
+                },
+            with_context,
+            ..
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let commitment = commitment.unwrap_or_default().commitment;
+        // same default encoding as the actual RPC
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let filters = filters.unwrap_or_default();
+        if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+            return Err(rpc_err(jsonrpc_core::Error::invalid_params(format!(
+                "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+            ))));
+        }
+        let filters = filters
+            .into_iter()
+            .map(verify_filter)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rpc_err(jsonrpc_core::Error::invalid_params(e.to_string())))?;
+
+        let accounts = self
+            .filtered_program_accounts(program_id, &filters, commitment)
+            .await?;
+        let res = self
+            .encode_keyed_accounts(accounts, encoding, data_slice)
+            .await?;
+        Ok(if with_context.unwrap_or(false) {
+            OptionalContext::Context(RpcResponse {
+                context: RpcResponseContext::new(self.curr_slot().await),
+                value: res,
+            })
+        } else {
+            OptionalContext::NoContext(res)
+        })
+    }
+
+    /// All tracked accounts owned by `program_id` that pass all `filters`, sorted by pubkey
+    async fn filtered_program_accounts(
+        &mut self,
+        program_id: Pubkey,
+        filters: &[RpcFilterType],
+        commitment: CommitmentLevel,
+    ) -> Result<Vec<(Pubkey, Account)>, Box<dyn Error + Send + Sync>> {
+        let mut res = Vec::new();
+        for key in self.account_index.pubkeys() {
+            let account = match self.bc.get_account_with_commitment(key, commitment).await? {
+                Some(a) => a,
+                None => continue,
+            };
+            if account.owner == program_id
+                && filters.iter().all(|f| filter_allows(f, &account.data))
+            {
+                res.push((key, account));
+            }
+        }
+        Ok(res)
+    }
+
+    async fn encode_keyed_accounts(
+        &mut self,
+        accounts: Vec<(Pubkey, Account)>,
+        encoding: UiAccountEncoding,
+        data_slice: Option<UiDataSliceConfig>,
+    ) -> Result<Vec<RpcKeyedAccount>, Box<dyn Error + Send + Sync>> {
+        let mut res = Vec::with_capacity(accounts.len());
+        for (key, account) in accounts {
+            res.push(RpcKeyedAccount {
+                pubkey: key.to_string(),
+                account: self
+                    .encode_account(&key, account, encoding, data_slice)
+                    .await?,
+            });
+        }
+        Ok(res)
+    }
+
+    /// All commitment levels point to the same bank in `ProgramTest`
+    pub async fn get_balance(
+        &mut self,
+        key: Pubkey,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let RpcContextConfig {
+            commitment,
+            min_context_slot,
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        Ok(self
+            .bc
+            .get_balance_with_commitment(key, commitment.unwrap_or_default().commitment)
+            .await?)
+    }
+
+    /// `transaction_count` is always `None` since [`BanksClient`] doesn't expose it
+    pub async fn get_epoch_info(
+        &mut self,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<EpochInfo, Box<dyn Error + Send + Sync>> {
+        let RpcContextConfig {
+            min_context_slot, ..
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let absolute_slot = self.curr_slot().await;
+        let epoch_schedule: EpochSchedule = self.bc.get_sysvar().await?;
+        let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(absolute_slot);
+        Ok(EpochInfo {
+            epoch,
+            slot_index,
+            slots_in_epoch: epoch_schedule.get_slots_in_epoch(epoch),
+            absolute_slot,
+            block_height: self.bc.get_root_block_height().await?,
+            transaction_count: None,
+        })
+    }
+
+    /// Returns `None` if the message's blockhash is not found, same as the actual RPC
+    pub async fn get_fee_for_message(
+        &mut self,
+        message: VersionedMessage,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        let RpcContextConfig {
+            commitment,
+            min_context_slot,
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let loaded_addresses = self.resolve_loaded_addresses(&message).await.map_err(|e| {
+            rpc_err(jsonrpc_core::Error::invalid_params(format!(
+                "invalid transaction message: {e}"
+            )))
+        })?;
+        Ok(self
+            .bc
+            .get_fee_for_message_with_commitment(
+                to_legacy_message(&message, &loaded_addresses),
+                commitment.unwrap_or_default().commitment,
+            )
+            .await?)
+    }
+
+    pub async fn get_minimum_balance_for_rent_exemption(
+        &mut self,
+        data_len: usize,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        Ok(self.bc.get_rent().await?.minimum_balance(data_len))
+    }
+
+    pub async fn get_slot(
+        &mut self,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let RpcContextConfig {
+            min_context_slot, ..
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        Ok(self.curr_slot().await)
+    }
+
+    pub fn get_recent_prioritization_fees(
+        &self,
+        writable_accounts: Vec<Pubkey>,
+    ) -> Result<Vec<RpcPrioritizationFee>, Box<dyn Error + Send + Sync>> {
+        if writable_accounts.len() > MAX_TX_ACCOUNT_LOCKS {
+            return Err(invalid_params(&format!(
+                "Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"
+            )));
+        }
+        Ok(self
+            .prioritization_fee_source
+            .read()
+            .unwrap()
+            .recent_prioritization_fees(&writable_accounts))
+    }
+
+    /// Same errors as the actual RPC
+    pub async fn get_token_account_balance(
+        &mut self,
+        key: Pubkey,
+        cfg: Option<CommitmentConfig>,
+    ) -> Result<UiTokenAmount, Box<dyn Error + Send + Sync>> {
+        let commitment = cfg.unwrap_or_default().commitment;
+        let account = self
+            .bc
+            .get_account_with_commitment(key, commitment)
+            .await?
+            .ok_or_else(|| invalid_params("Invalid param: could not find account"))?;
+        if !is_known_spl_token_id(&account.owner) {
+            return Err(invalid_params("Invalid param: not a Token account"));
+        }
+        let token_account = StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .map_err(|_| invalid_params("Invalid param: not a Token account"))?;
+        let mint_data = self
+            .get_spl_token_additional_data(token_account.base.mint, commitment)
+            .await?;
+        Ok(token_amount_to_ui_amount_v2(
+            token_account.base.amount,
+            &mint_data,
+        ))
+    }
+
+    /// Same errors as the actual RPC
+    async fn get_spl_token_additional_data(
+        &mut self,
+        mint: Pubkey,
+        commitment: CommitmentLevel,
+    ) -> Result<SplTokenAdditionalData, Box<dyn Error + Send + Sync>> {
+        let mint_account = self
+            .bc
+            .get_account_with_commitment(mint, commitment)
+            .await?
+            .ok_or_else(|| invalid_params("Invalid param: could not find mint"))?;
+        let Clock { unix_timestamp, .. } = self.bc.get_sysvar().await?;
+        spl_token_additional_data(&mint_account.data, unix_timestamp)
+            .ok_or_else(|| invalid_params("Invalid param: mint could not be unpacked"))
+    }
+
+    /// [`BanksClient`] can't enumerate accounts, so this only returns the accounts
+    /// the server knows about, see [`Self::register_accounts`]
+    pub async fn get_token_accounts_by_owner(
+        &mut self,
+        owner: Pubkey,
+        filter: TokenAccountsFilter,
+        cfg: Option<RpcAccountInfoConfig>,
+    ) -> Result<Vec<RpcKeyedAccount>, Box<dyn Error + Send + Sync>> {
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice,
+            commitment,
+            min_context_slot,
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let commitment = commitment.unwrap_or_default().commitment;
+        // same default encoding as the actual RPC
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let (program_id, mint) = match filter {
+            TokenAccountsFilter::Mint(mint) => {
+                let mint_account = self
+                    .bc
+                    .get_account_with_commitment(mint, commitment)
+                    .await?
+                    .ok_or_else(|| invalid_params("Invalid param: could not find mint"))?;
+                if !is_known_spl_token_id(&mint_account.owner) {
+                    return Err(invalid_params("Invalid param: not a Token mint"));
+                }
+                (mint_account.owner, Some(mint))
+            }
+            TokenAccountsFilter::ProgramId(program_id) => {
+                if !is_known_spl_token_id(&program_id) {
+                    return Err(invalid_params(
+                        "Invalid param: unrecognized Token program id",
+                    ));
+                }
+                (program_id, None)
+            }
+        };
+        let mut accounts = self
+            .filtered_program_accounts(program_id, &[RpcFilterType::TokenAccountState], commitment)
+            .await?;
+        accounts.retain(|(_, account)| {
+            TokenAccount::unpack_account_owner(&account.data) == Some(&owner)
+                && mint.is_none_or(|mint| {
+                    TokenAccount::unpack_account_mint(&account.data) == Some(&mint)
+                })
+        });
+        self.encode_keyed_accounts(accounts, encoding, data_slice)
+            .await
+    }
+
+    /// Unlike the actual RPC, this does not check the blockhash's age,
+    /// only that it is still in the bank's blockhash queue.
+    ///
+    /// [`BanksClient`] does not expose the blockhash queue, so this quotes the fee of an
+    /// empty message with `blockhash` and [`Pubkey::default()`] as the fee payer instead.
+    /// The bank only returns a fee for messages whose blockhash is in its queue,
+    /// and quoting a fee does not load the fee payer so it does not need to exist.
+    pub async fn is_blockhash_valid(
+        &mut self,
+        blockhash: Hash,
+        cfg: Option<RpcContextConfig>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let RpcContextConfig {
+            commitment,
+            min_context_slot,
+        } = cfg.unwrap_or_default();
+        self.check_min_context_slot(min_context_slot).await?;
+        let message = Message::new_with_blockhash(&[], Some(&Pubkey::default()), &blockhash);
+        Ok(self
+            .bc
+            .get_fee_for_message_with_commitment(message, commitment.unwrap_or_default().commitment)
+            .await?
+            .is_some())
+    }
+
+    /// Loads the addresses referenced by the message's address lookup tables, if any
+    pub async fn resolve_loaded_addresses(
+        &mut self,
+        message: &VersionedMessage,
+    ) -> Result<LoadedAddresses, Box<dyn Error + Send + Sync>> {
+        let mut res = LoadedAddresses::default();
+        for MessageAddressTableLookup {
+            account_key,
+            writable_indexes,
+            readonly_indexes,
+        } in message.address_table_lookups().unwrap_or_default()
+        {
+            let lut_account = self
+                .bc
+                .get_account(*account_key)
+                .await?
+                .ok_or("Address lookup table not found")?;
+            let lut = AddressLookupTable::deserialize(&lut_account.data)?;
+            for (indexes, loaded) in [
+                (writable_indexes, &mut res.writable),
+                (readonly_indexes, &mut res.readonly),
+            ] {
+                for i in indexes {
+                    loaded.push(
+                        *lut.addresses
+                            .get(usize::from(*i))
+                            .ok_or("Invalid address lookup table index")?,
+                    );
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    /// Processes the transaction against the bank, records its result and metadata
+    /// for `getSignatureStatuses` and `getTransaction` and notifies pubsub subscribers.
+    ///
+    /// Transactions that were not executed, e.g. due to an invalid blockhash, are not recorded,
+    /// like how they would be dropped on an actual cluster
+    pub async fn process_transaction(
+        &mut self,
+        tx: VersionedTransaction,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.process_transaction_with_metadata(tx).await?;
+        Ok(())
+    }
+
+    /// [`Self::process_transaction`], also returning the bank's result
+    pub async fn process_transaction_with_metadata(
+        &mut self,
+        tx: VersionedTransaction,
+    ) -> Result<BanksTransactionResultWithMetadata, Box<dyn Error + Send + Sync>> {
+        let loaded_addresses = self.resolve_loaded_addresses(&tx.message).await?;
+        let keys: Vec<Pubkey> =
+            AccountKeys::new(tx.message.static_account_keys(), Some(&loaded_addresses))
+                .iter()
+                .copied()
+                .collect();
+        let writable_accounts = keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| tx.message.is_maybe_writable(*i, None))
+            .map(|(_, key)| *key)
+            .collect();
+        let fee = self
+            .bc
+            .get_fee_for_message(to_legacy_message(&tx.message, &loaded_addresses))
+            .await?
+            .unwrap_or_default();
+        let pre_balances = self.get_balances(&keys).await?;
+        let Clock {
+            slot,
+            unix_timestamp,
+            ..
+        } = self.bc.get_sysvar().await?;
+
+        let res = self
+            .bc
+            .process_transaction_with_metadata(tx.clone())
+            .await?;
+        let metadata = match &res.metadata {
+            Some(m) => m.clone(),
+            None => return Ok(res),
+        };
+
+        self.account_index.extend(keys.iter().copied());
+        let post_balances = self.get_balances(&keys).await?;
+        let signature = tx.signatures[0];
+        let err = res.result.clone().err();
+        let confirmed = VersionedConfirmedTransactionWithStatusMeta {
+            slot,
+            tx_with_meta: VersionedTransactionWithStatusMeta {
+                transaction: tx,
+                meta: TransactionStatusMeta {
+                    status: res.result.clone(),
+                    fee,
+                    pre_balances,
+                    post_balances,
+                    inner_instructions: None,
+                    log_messages: Some(metadata.log_messages),
+                    pre_token_balances: None,
+                    post_token_balances: None,
+                    rewards: None,
+                    loaded_addresses,
+                    return_data: metadata.return_data,
+                    compute_units_consumed: Some(metadata.compute_units_consumed),
+                },
+            },
+            block_time: Some(unix_timestamp),
+        };
+        self.processed_txs
+            .write()
+            .unwrap()
+            .insert(signature, confirmed);
+        // only errors if there are no pubsub connections
+        let _ = self.processed_tx_events.send(ProcessedTxEvent {
+            signature,
+            slot,
+            err,
+            writable_accounts,
+        });
+        Ok(res)
+    }
+
+    async fn get_balances(
+        &mut self,
+        keys: &[Pubkey],
+    ) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
         let mut res = Vec::with_capacity(keys.len());
-        let ds = cfg.map_or_else(|| None, |c| c.data_slice);
         for key in keys {
-            res.push(self.bc.get_account(key).await?.map(|account| {
-                UiAccount::encode(
-                    &key,
-                    &account_data_sliced(account, ds),
-                    UiAccountEncoding::Base64,
-                    None,
-                    None,
+            res.push(self.bc.get_balance(*key).await?);
+        }
+        Ok(res)
+    }
+
+    /// The bank of `ProgramTest` is always rooted,
+    /// so all processed transactions are reported as finalized
+    pub async fn get_signature_statuses(
+        &mut self,
+        signatures: Vec<Signature>,
+        _cfg: Option<RpcSignatureStatusConfig>,
+    ) -> Result<Vec<Option<TransactionStatus>>, Box<dyn Error + Send + Sync>> {
+        if signatures.len() > MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS {
+            return Err(rpc_err(jsonrpc_core::Error::invalid_params(format!(
+                "Too many inputs provided; max {MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS}"
+            ))));
+        }
+        let processed_txs = self.processed_txs.read().unwrap();
+        Ok(signatures
+            .iter()
+            .map(|signature| {
+                processed_txs.get(signature).map(|confirmed| {
+                    let status = confirmed.tx_with_meta.meta.status.clone();
+                    TransactionStatus {
+                        slot: confirmed.slot,
+                        confirmations: None,
+                        err: status.clone().err(),
+                        status,
+                        confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+                    }
+                })
+            })
+            .collect())
+    }
+
+    pub async fn get_transaction(
+        &mut self,
+        signature: Signature,
+        cfg: Option<RpcTransactionConfig>,
+    ) -> Result<Option<EncodedConfirmedTransactionWithStatusMeta>, Box<dyn Error + Send + Sync>>
+    {
+        let RpcTransactionConfig {
+            encoding,
+            max_supported_transaction_version,
+            ..
+        } = cfg.unwrap_or_default();
+        let VersionedConfirmedTransactionWithStatusMeta {
+            slot,
+            tx_with_meta,
+            block_time,
+        } = match self.processed_txs.read().unwrap().get(&signature) {
+            Some(confirmed) => confirmed.clone(),
+            None => return Ok(None),
+        };
+        let encoded = ConfirmedTransactionWithStatusMeta {
+            slot,
+            tx_with_meta: TransactionWithStatusMeta::Complete(tx_with_meta),
+            block_time,
+        }
+        .encode(
+            encoding.unwrap_or(UiTransactionEncoding::Json),
+            max_supported_transaction_version,
+        )
+        .map_err(|e| rpc_err(RpcCustomError::from(e)))?;
+        Ok(Some(encoded))
+    }
+
+    /// Runs the same preflight checks as the actual RPC unless `cfg.skip_preflight`,
+    /// then processes the transaction against the bank.
+    ///
+    /// Transactions with invalid signatures are silently dropped if preflight is skipped,
+    /// like how they would never land on an actual cluster
+    pub async fn send_transaction(
+        &mut self,
+        tx: VersionedTransaction,
+        cfg: Option<RpcSendTransactionConfig>,
+    ) -> Result<Signature, Box<dyn Error + Send + Sync>> {
+        let RpcSendTransactionConfig {
+            skip_preflight,
+            preflight_commitment,
+            ..
+        } = cfg.unwrap_or_default();
+        // decode_tx() sanitizes, so theres always at least 1 signature
+        let signature = tx.signatures[0];
+        let is_sig_verified = is_sig_verified(&tx);
+        if !skip_preflight {
+            if !is_sig_verified {
+                return Err(rpc_err(
+                    RpcCustomError::TransactionSignatureVerificationFailure,
+                ));
+            }
+            let result = self
+                .simulate_transaction(
+                    tx.clone(),
+                    Some(RpcSimulateTransactionConfig {
+                        commitment: preflight_commitment
+                            .map(|commitment| CommitmentConfig { commitment }),
+                        ..Default::default()
+                    }),
                 )
-            }));
+                .await?;
+            if let Some(err) = &result.err {
+                return Err(rpc_err(RpcCustomError::SendTransactionPreflightFailure {
+                    message: format!("Transaction simulation failed: {err}"),
+                    result,
+                }));
+            }
+        }
+        if is_sig_verified {
+            self.process_transaction(tx).await?;
+        }
+        Ok(signature)
+    }
+
+    /// `cfg.accounts` is not supported since [`BanksClient`] does not return post-simulation accounts
+    pub async fn simulate_transaction(
+        &mut self,
+        mut tx: VersionedTransaction,
+        cfg: Option<RpcSimulateTransactionConfig>,
+    ) -> Result<RpcSimulateTransactionResult, Box<dyn Error + Send + Sync>> {
+        let RpcSimulateTransactionConfig {
+            sig_verify,
+            replace_recent_blockhash,
+            commitment,
+            accounts,
+            inner_instructions,
+            ..
+        } = cfg.unwrap_or_default();
+        if sig_verify && replace_recent_blockhash {
+            return Err(rpc_err(jsonrpc_core::Error::invalid_params(
+                "sigVerify may not be used with replaceRecentBlockhash",
+            )));
+        }
+        if accounts.is_some() {
+            return Err(rpc_err(jsonrpc_core::Error::invalid_params(
+                "accounts is not supported",
+            )));
+        }
+        if sig_verify && !is_sig_verified(&tx) {
+            return Err(rpc_err(
+                RpcCustomError::TransactionSignatureVerificationFailure,
+            ));
+        }
+        let commitment = commitment.unwrap_or_default().commitment;
+        let replacement_blockhash = if replace_recent_blockhash {
+            let (blockhash, last_valid_block_height) = self
+                .bc
+                .get_latest_blockhash_with_commitment(commitment)
+                .await?
+                .ok_or("Latest blockhash not found")?;
+            tx.message.set_recent_blockhash(blockhash);
+            Some(RpcBlockhash {
+                blockhash: blockhash.to_string(),
+                last_valid_block_height,
+            })
+        } else {
+            None
+        };
+        let loaded_addresses = if inner_instructions {
+            Some(self.resolve_loaded_addresses(&tx.message).await?)
+        } else {
+            None
+        };
+        let static_account_keys = tx.message.static_account_keys().to_vec();
+        let sim = self
+            .bc
+            .simulate_transaction_with_commitment(tx, commitment)
+            .await?;
+        let mut res = RpcSimulateTransactionResult {
+            err: sim.result.and_then(|r| r.err()),
+            logs: None,
+            accounts: None,
+            units_consumed: None,
+            return_data: None,
+            inner_instructions: None,
+            replacement_blockhash,
+        };
+        if let Some(details) = sim.simulation_details {
+            res.logs = Some(details.logs);
+            res.units_consumed = Some(details.units_consumed);
+            res.return_data = details.return_data.map(Into::into);
+            if let (Some(loaded_addresses), Some(inner_ixs)) =
+                (loaded_addresses, details.inner_instructions)
+            {
+                let account_keys = AccountKeys::new(&static_account_keys, Some(&loaded_addresses));
+                res.inner_instructions = Some(
+                    map_inner_instructions(inner_ixs)
+                        .map(|ixs| UiInnerInstructions::parse(ixs, &account_keys))
+                        .collect(),
+                );
+            }
         }
         Ok(res)
     }
@@ -167,7 +1163,88 @@ impl BanksRpcServer {
         Ok(serde_json::to_value(res).unwrap())
     }
 
+    /// [`jsonrpc_core::Error`]s returned by the method are written to the response's `error` field.
+    ///
+    /// Injects the JSON-RPC level faults set via [`Self::set_faults`].
+    /// HTTP level faults are only injected by the HTTP server
     pub async fn handle_req(
+        &mut self,
+        req: JsonRpcReq,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let faults = self.faults.write().unwrap().draw(req.method);
+        self.handle_req_with_faults(req, &faults).await
+    }
+
+    /// Records the request and the response with the faults injected, if recording
+    async fn handle_req_with_faults(
+        &mut self,
+        req: JsonRpcReq,
+        faults: &[Fault],
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let request = self
+            .recorder
+            .lock()
+            .unwrap()
+            .is_some()
+            .then(|| serde_json::to_value(&req).unwrap());
+        let response = self.respond_with_faults(req, faults).await?;
+        if let Some(request) = request {
+            if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+                recorder.record(&RecordedExchange {
+                    request,
+                    response: response.clone(),
+                })?;
+            }
+        }
+        Ok(response)
+    }
+
+    async fn respond_with_faults(
+        &mut self,
+        req: JsonRpcReq,
+        faults: &[Fault],
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let id = req.id;
+        let mut stale_slots = 0;
+        let mut drop_tx = false;
+        for fault in faults {
+            match fault {
+                Fault::RpcError(e) => return Ok(JsonRpcErrResp::new(id, e.clone()).into()),
+                Fault::StaleContextSlot(n) => stale_slots = stale_slots.max(*n),
+                Fault::DropTransaction => drop_tx = true,
+                Fault::Latency(_) | Fault::HttpStatus(_) => (),
+            }
+        }
+        let res = match req.method {
+            RpcMethod::SendTransaction if drop_tx => Self::drop_transaction(req),
+            _ => self.dispatch_req(req).await,
+        };
+        let mut resp = match res {
+            Ok(resp) => resp,
+            Err(e) => match e.downcast::<jsonrpc_core::Error>() {
+                Ok(rpc_err) => return Ok(JsonRpcErrResp::new(id, *rpc_err).into()),
+                Err(e) => return Err(e),
+            },
+        };
+        if let Some(slot) = resp.pointer_mut("/result/context/slot") {
+            if let Some(s) = slot.as_u64() {
+                *slot = s.saturating_sub(stale_slots).into();
+            }
+        }
+        Ok(resp)
+    }
+
+    /// Responds to a `sendTransaction` request as if the transaction was sent
+    /// but never lands
+    fn drop_transaction(
+        JsonRpcReq { id, params, .. }: JsonRpcReq,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let (encoded_tx, cfg) = deser_send_transaction_params(params)?;
+        let tx = decode_tx(&encoded_tx, cfg.and_then(|c| c.encoding)).map_err(rpc_err)?;
+        Ok(JsonRpcResp::new(id, tx.signatures[0].to_string()).into())
+    }
+
+    async fn dispatch_req(
         &mut self,
         JsonRpcReq {
             jsonrpc: _,
@@ -186,6 +1263,29 @@ impl BanksRpcServer {
                 )
                 .into()
             }
+            RpcMethod::GetBalance => {
+                let (key, cfg) = deser_get_balance_params(params)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.get_balance(key, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
+            RpcMethod::GetEpochInfo => {
+                let cfg = deser_get_epoch_info_params(params)?;
+                JsonRpcResp::new(id, self.get_epoch_info(cfg).await?).into()
+            }
+            RpcMethod::GetFeeForMessage => {
+                let (encoded_message, cfg) = deser_get_fee_for_message_params(params)?;
+                let message = decode_message(&encoded_message).map_err(rpc_err)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.get_fee_for_message(message, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
             RpcMethod::GetLatestBlockhash => {
                 let cfg = deser_get_latest_blockhash_params(params)?;
                 JsonRpcResp::with_ctx(
@@ -195,6 +1295,15 @@ impl BanksRpcServer {
                 )
                 .into()
             }
+            RpcMethod::GetMinimumBalanceForRentExemption => {
+                let (data_len, _cfg) = deser_get_minimum_balance_for_rent_exemption_params(params)?;
+                JsonRpcResp::new(
+                    id,
+                    self.get_minimum_balance_for_rent_exemption(data_len)
+                        .await?,
+                )
+                .into()
+            }
             RpcMethod::GetMultipleAccounts => {
                 let (keys, cfg) = deser_get_multiple_accounts_params(params)?;
                 JsonRpcResp::with_ctx(
@@ -204,6 +1313,49 @@ impl BanksRpcServer {
                 )
                 .into()
             }
+            RpcMethod::GetProgramAccounts => {
+                let (program_id, cfg) = deser_get_program_accounts_params(params)?;
+                JsonRpcResp::new(id, self.get_program_accounts(program_id, cfg).await?).into()
+            }
+            RpcMethod::GetRecentPrioritizationFees => {
+                let writable_accounts = deser_get_recent_prioritization_fees_params(params)?;
+                JsonRpcResp::new(id, self.get_recent_prioritization_fees(writable_accounts)?).into()
+            }
+            RpcMethod::GetSignatureStatuses => {
+                let (signatures, cfg) = deser_get_signature_statuses_params(params)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.get_signature_statuses(signatures, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
+            RpcMethod::GetSlot => {
+                let cfg = deser_get_slot_params(params)?;
+                JsonRpcResp::new(id, self.get_slot(cfg).await?).into()
+            }
+            RpcMethod::GetTokenAccountBalance => {
+                let (key, cfg) = deser_get_token_account_balance_params(params)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.get_token_account_balance(key, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
+            RpcMethod::GetTokenAccountsByOwner => {
+                let (owner, filter, cfg) = deser_get_token_accounts_by_owner_params(params)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.get_token_accounts_by_owner(owner, filter, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
+            RpcMethod::GetTransaction => {
+                let (signature, cfg) = deser_get_transaction_params(params)?;
+                JsonRpcResp::new(id, self.get_transaction(signature, cfg).await?).into()
+            }
             RpcMethod::GetVersion => {
                 let version = solana_version::Version::default();
                 JsonRpcResp::new(
@@ -215,6 +1367,51 @@ impl BanksRpcServer {
                 )
                 .into()
             }
+            RpcMethod::IsBlockhashValid => {
+                let (blockhash, cfg) = deser_is_blockhash_valid_params(params)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.is_blockhash_valid(blockhash, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
+            RpcMethod::SanctumAirdrop => {
+                let (key, lamports) = deser_sanctum_airdrop_params(params)?;
+                JsonRpcResp::new(id, self.sanctum_airdrop(key, lamports).await?).into()
+            }
+            RpcMethod::SanctumSetAccount => {
+                let (key, account) = deser_sanctum_set_account_params(params)?;
+                JsonRpcResp::new(id, self.sanctum_set_account(key, account).await?).into()
+            }
+            RpcMethod::SanctumSetClock => {
+                let cfg = deser_sanctum_set_clock_params(params)?;
+                JsonRpcResp::new(id, self.sanctum_set_clock(cfg).await?).into()
+            }
+            RpcMethod::SanctumWarpToEpoch => {
+                let epoch = deser_sanctum_warp_to_epoch_params(params)?;
+                JsonRpcResp::new(id, self.sanctum_warp_to_epoch(epoch).await?).into()
+            }
+            RpcMethod::SanctumWarpToSlot => {
+                let slot = deser_sanctum_warp_to_slot_params(params)?;
+                JsonRpcResp::new(id, self.sanctum_warp_to_slot(slot).await?).into()
+            }
+            RpcMethod::SendTransaction => {
+                let (encoded_tx, cfg) = deser_send_transaction_params(params)?;
+                let tx = decode_tx(&encoded_tx, cfg.and_then(|c| c.encoding)).map_err(rpc_err)?;
+                JsonRpcResp::new(id, self.send_transaction(tx, cfg).await?.to_string()).into()
+            }
+            RpcMethod::SimulateTransaction => {
+                let (encoded_tx, cfg) = deser_simulate_transaction_params(params)?;
+                let tx = decode_tx(&encoded_tx, cfg.as_ref().and_then(|c| c.encoding))
+                    .map_err(rpc_err)?;
+                JsonRpcResp::with_ctx(
+                    id,
+                    self.simulate_transaction(tx, cfg).await?,
+                    self.curr_slot().await,
+                )
+                .into()
+            }
         })
     }
 }
@@ -232,13 +1429,47 @@ impl Service<Request<Incoming>> for BanksRpcServer {
         let mut this = self.clone();
         Box::pin(async move {
             let body = req.into_body().collect().await.ok().unwrap().to_bytes();
-            let resp = if let Ok(v) = serde_json::from_reader(body.clone().reader()) {
-                this.handle_batched_reqs(v).await
-            } else if let Ok(r) = serde_json::from_reader(body.reader()) {
-                this.handle_req(r).await
+            let (reqs, is_batch): (Vec<JsonRpcReq>, bool) =
+                if let Ok(v) = serde_json::from_reader(body.clone().reader()) {
+                    (v, true)
+                } else if let Ok(r) = serde_json::from_reader(body.reader()) {
+                    (vec![r], false)
+                } else {
+                    return Err("Invalid request".into());
+                };
+
+            let faults: Vec<Vec<Fault>> = {
+                let mut injector = this.faults.write().unwrap();
+                reqs.iter().map(|r| injector.draw(r.method)).collect()
+            };
+            let latency = faults
+                .iter()
+                .flatten()
+                .filter_map(|f| match f {
+                    Fault::Latency(d) => Some(*d),
+                    _ => None,
+                })
+                .max();
+            if let Some(latency) = latency {
+                tokio::time::sleep(latency).await;
+            }
+            let http_status = faults.iter().flatten().find_map(|f| match f {
+                Fault::HttpStatus(s) => Some(*s),
+                _ => None,
+            });
+            if let Some(http_status) = http_status {
+                return Ok(to_http_status_resp(http_status));
+            }
+
+            let mut resps = Vec::with_capacity(reqs.len());
+            for (req, faults) in reqs.into_iter().zip(faults) {
+                resps.push(this.handle_req_with_faults(req, &faults).await?);
+            }
+            let resp = if is_batch {
+                Value::Array(resps)
             } else {
-                Err("Invalid request".into())
-            }?;
+                resps.pop().unwrap()
+            };
             Ok(to_http_resp(serde_json::to_vec(&resp).unwrap().into()))
         })
     }
@@ -0,0 +1,86 @@
+use std::{cmp, collections::HashMap, fmt::Debug, sync::Arc};
+
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::response::RpcPrioritizationFee;
+
+/// Callback that receives the writable accounts passed to `getRecentPrioritizationFees`
+/// and returns the samples to respond with
+pub type PrioritizationFeeCallback =
+    Arc<dyn Fn(&[Pubkey]) -> Vec<RpcPrioritizationFee> + Send + Sync>;
+
+/// Where [`super::BanksRpcServer`] gets its `getRecentPrioritizationFees` responses from,
+/// since `ProgramTest` does not track prioritization fees
+#[derive(Clone)]
+pub enum PrioritizationFeeSource {
+    /// Same samples regardless of the writable accounts requested
+    Fixed(Vec<RpcPrioritizationFee>),
+
+    /// Like the actual RPC, each slot's fee is the max of the slot's sample
+    /// and the fees of the requested writable accounts
+    PerAccount {
+        samples: Vec<RpcPrioritizationFee>,
+        account_fees: HashMap<Pubkey, u64>,
+    },
+
+    Callback(PrioritizationFeeCallback),
+}
+
+impl Default for PrioritizationFeeSource {
+    /// No samples, like a cluster that has not processed any prioritized transactions
+    fn default() -> Self {
+        Self::Fixed(Vec::new())
+    }
+}
+
+impl Debug for PrioritizationFeeSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(samples) => f.debug_tuple("Fixed").field(samples).finish(),
+            Self::PerAccount {
+                samples,
+                account_fees,
+            } => f
+                .debug_struct("PerAccount")
+                .field("samples", samples)
+                .field("account_fees", account_fees)
+                .finish(),
+            Self::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+impl PrioritizationFeeSource {
+    pub fn callback(
+        f: impl Fn(&[Pubkey]) -> Vec<RpcPrioritizationFee> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Callback(Arc::new(f))
+    }
+
+    pub fn recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Vec<RpcPrioritizationFee> {
+        match self {
+            Self::Fixed(samples) => samples.clone(),
+            Self::PerAccount {
+                samples,
+                account_fees,
+            } => {
+                let max_account_fee = writable_accounts
+                    .iter()
+                    .filter_map(|account| account_fees.get(account))
+                    .max()
+                    .copied()
+                    .unwrap_or_default();
+                samples
+                    .iter()
+                    .map(|sample| RpcPrioritizationFee {
+                        slot: sample.slot,
+                        prioritization_fee: cmp::max(sample.prioritization_fee, max_account_fee),
+                    })
+                    .collect()
+            }
+            Self::Callback(f) => f(writable_accounts),
+        }
+    }
+}
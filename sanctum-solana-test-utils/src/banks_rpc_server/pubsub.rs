@@ -0,0 +1,379 @@
+//! Websocket pubsub endpoint of [`BanksRpcServer`].
+//!
+//! Supports `accountSubscribe`, `signatureSubscribe`, `slotSubscribe` and their `*Unsubscribe`s.
+//! Account and signature notifications are pushed when a transaction processed by the server
+//! (or any of its clones) writes to the subscribed account or has the subscribed signature.
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use solana_account_decoder::{UiAccount, UiAccountEncoding, UiDataSliceConfig};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::BanksClient;
+use solana_rpc_client_api::{
+    config::RpcAccountInfoConfig,
+    response::{ProcessedSignatureResult, RpcSignatureResult, SlotInfo},
+};
+use solana_sdk::{signature::Signature, transaction::TransactionError};
+use std::{collections::HashMap, error::Error, time::Duration};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast::error::RecvError,
+    task::JoinHandle,
+};
+use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+
+use super::{
+    invalid_params,
+    json_rpc::{
+        deser_account_subscribe_params, deser_signature_subscribe_params, deser_unsubscribe_params,
+        JsonRpcErrResp, JsonRpcNotification, JsonRpcPubsubReq, JsonRpcResp, PubsubMethod,
+    },
+    BanksRpcServer,
+};
+
+/// Number of unconsumed [`ProcessedTxEvent`]s buffered per websocket connection
+/// before the oldest ones are dropped
+pub(crate) const PROCESSED_TX_EVENTS_CAPACITY: usize = 1024;
+
+/// `ProgramTest` has no slot notifications to hook into, so slot changes are polled for
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Broadcast by [`BanksRpcServer::process_transaction`] for every executed transaction
+#[derive(Clone, Debug)]
+pub(crate) struct ProcessedTxEvent {
+    pub signature: Signature,
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+    pub writable_accounts: Vec<Pubkey>,
+}
+
+#[derive(Clone, Debug)]
+enum Subscription {
+    Account {
+        key: Pubkey,
+        encoding: UiAccountEncoding,
+        data_slice: Option<UiDataSliceConfig>,
+    },
+    Signature(Signature),
+    Slot,
+}
+
+/// Subscriptions of a single websocket connection
+#[derive(Debug, Default)]
+struct Subscriptions {
+    next_id: u64,
+    subs: HashMap<u64, Subscription>,
+}
+
+impl Subscriptions {
+    fn new_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn insert(&mut self, sub: Subscription) -> u64 {
+        let id = self.new_id();
+        self.subs.insert(id, sub);
+        id
+    }
+
+    /// Only removes the subscription if it was created by the given method
+    fn remove(&mut self, id: u64, method: PubsubMethod) -> bool {
+        let matches = matches!(
+            (method, self.subs.get(&id)),
+            (
+                PubsubMethod::AccountUnsubscribe,
+                Some(Subscription::Account { .. })
+            ) | (
+                PubsubMethod::SignatureUnsubscribe,
+                Some(Subscription::Signature(_))
+            ) | (PubsubMethod::SlotUnsubscribe, Some(Subscription::Slot))
+        );
+        if matches {
+            self.subs.remove(&id);
+        }
+        matches
+    }
+
+    fn has_slot_subs(&self) -> bool {
+        self.subs.values().any(|s| matches!(s, Subscription::Slot))
+    }
+}
+
+fn signature_notification(subscription: u64, slot: u64, err: Option<TransactionError>) -> Value {
+    JsonRpcNotification::with_ctx(
+        "signatureNotification",
+        subscription,
+        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err }),
+        slot,
+    )
+    .into()
+}
+
+/// HTTP and websocket pubsub servers spawned by
+/// [`BanksRpcServer::spawn_random_unused_with_pubsub`]
+pub struct SpawnedServers {
+    pub rpc_port: u16,
+    pub pubsub_port: u16,
+    pub rpc_jh: JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>,
+    pub pubsub_jh: JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>,
+}
+
+impl BanksRpcServer {
+    /// Spawns both the HTTP server and the websocket pubsub server
+    /// on `127.0.0.1:{random_unused_port}` (IPV4).
+    pub async fn spawn_random_unused_with_pubsub(bc: BanksClient) -> SpawnedServers {
+        let s = Self::new(bc);
+        let http_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let rpc_port = http_listener.local_addr().unwrap().port();
+        let pubsub_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let pubsub_port = pubsub_listener.local_addr().unwrap().port();
+        SpawnedServers {
+            rpc_port,
+            pubsub_port,
+            rpc_jh: s.clone().spawn(http_listener),
+            pubsub_jh: s.spawn_pubsub(pubsub_listener),
+        }
+    }
+
+    /// Spawn the websocket pubsub server in the background.
+    ///
+    /// Notifications are only sent for transactions processed by this server or its clones,
+    /// so spawn the HTTP server with a clone of the same server
+    pub fn spawn_pubsub(
+        self,
+        tcp_listener: TcpListener,
+    ) -> JoinHandle<Result<(), Box<dyn Error + Send + Sync>>> {
+        tokio::task::spawn(async move {
+            loop {
+                let (tcp_stream, _socket_addr) = tcp_listener.accept().await?;
+                let this = self.clone();
+                tokio::task::spawn(async move {
+                    let res = match tokio_tungstenite::accept_async(tcp_stream).await {
+                        Ok(ws) => this.serve_pubsub_connection(ws).await,
+                        Err(e) => Err(e.into()),
+                    };
+                    if let Err(err) = res {
+                        eprintln!("Error serving pubsub connection: {:?}", err);
+                    }
+                });
+            }
+        })
+    }
+
+    async fn serve_pubsub_connection(
+        mut self,
+        ws: WebSocketStream<TcpStream>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let (mut sink, mut stream) = ws.split();
+        let mut events = self.processed_tx_events.subscribe();
+        let mut subs = Subscriptions::default();
+        let mut slot_interval = tokio::time::interval(SLOT_POLL_INTERVAL);
+        let mut last_slot = self.curr_slot().await;
+        loop {
+            let msgs = tokio::select! {
+                msg = stream.next() => {
+                    let text = match msg {
+                        None => return Ok(()),
+                        Some(msg) => match msg? {
+                            Message::Text(text) => text,
+                            Message::Close(_) => return Ok(()),
+                            // flush the automatically queued pong
+                            Message::Ping(_) => {
+                                sink.flush().await?;
+                                continue;
+                            }
+                            _ => continue,
+                        },
+                    };
+                    self.handle_pubsub_msg(&text, &mut subs).await?
+                }
+                event = events.recv() => match event {
+                    Ok(event) => self.processed_tx_notifications(event, &mut subs).await?,
+                    // missed notifications can't be recovered, move on
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                _ = slot_interval.tick() => {
+                    if !subs.has_slot_subs() {
+                        continue;
+                    }
+                    let slot = self.curr_slot().await;
+                    if slot == last_slot {
+                        continue;
+                    }
+                    let slot_info = SlotInfo {
+                        slot,
+                        parent: last_slot,
+                        // ProgramTest's bank is always rooted
+                        root: slot,
+                    };
+                    last_slot = slot;
+                    subs.subs
+                        .iter()
+                        .filter(|(_, s)| matches!(s, Subscription::Slot))
+                        .map(|(id, _)| JsonRpcNotification::new("slotNotification", *id, slot_info).into())
+                        .collect()
+                }
+            };
+            for msg in msgs {
+                sink.send(Message::Text(msg.to_string())).await?;
+            }
+        }
+    }
+
+    /// Returns the response to the request followed by any notifications to send immediately
+    async fn handle_pubsub_msg(
+        &mut self,
+        text: &str,
+        subs: &mut Subscriptions,
+    ) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let req: JsonRpcPubsubReq = match serde_json::from_str(text) {
+            Ok(r) => r,
+            Err(e) => {
+                let mut error = jsonrpc_core::Error::invalid_request();
+                error.data = Some(e.to_string().into());
+                return Ok(vec![serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": error,
+                })]);
+            }
+        };
+        let id = req.id;
+        match self.dispatch_pubsub_req(req, subs).await {
+            Ok(resp) => Ok(resp),
+            Err(e) => match e.downcast::<jsonrpc_core::Error>() {
+                Ok(rpc_err) => Ok(vec![JsonRpcErrResp::new(id, *rpc_err).into()]),
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    async fn dispatch_pubsub_req(
+        &mut self,
+        JsonRpcPubsubReq {
+            jsonrpc: _,
+            id,
+            method,
+            params,
+        }: JsonRpcPubsubReq,
+        subs: &mut Subscriptions,
+    ) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let deser_err = |e: serde_json::Error| invalid_params(&e.to_string());
+        Ok(match method {
+            PubsubMethod::AccountSubscribe => {
+                let (key, cfg) = deser_account_subscribe_params(params).map_err(deser_err)?;
+                let RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    ..
+                } = cfg.unwrap_or_default();
+                let sub_id = subs.insert(Subscription::Account {
+                    key,
+                    // same default encoding as the actual RPC
+                    encoding: encoding.unwrap_or(UiAccountEncoding::Binary),
+                    data_slice,
+                });
+                vec![JsonRpcResp::new(id, sub_id).into()]
+            }
+            PubsubMethod::SignatureSubscribe => {
+                let (signature, _cfg) =
+                    deser_signature_subscribe_params(params).map_err(deser_err)?;
+                let already_processed =
+                    self.processed_txs
+                        .read()
+                        .unwrap()
+                        .get(&signature)
+                        .map(|confirmed| {
+                            (
+                                confirmed.slot,
+                                confirmed.tx_with_meta.meta.status.clone().err(),
+                            )
+                        });
+                match already_processed {
+                    // signature subscriptions are cancelled after the first notification,
+                    // so dont bother registering
+                    Some((slot, err)) => {
+                        let sub_id = subs.new_id();
+                        vec![
+                            JsonRpcResp::new(id, sub_id).into(),
+                            signature_notification(sub_id, slot, err),
+                        ]
+                    }
+                    None => {
+                        let sub_id = subs.insert(Subscription::Signature(signature));
+                        vec![JsonRpcResp::new(id, sub_id).into()]
+                    }
+                }
+            }
+            PubsubMethod::SlotSubscribe => {
+                let sub_id = subs.insert(Subscription::Slot);
+                vec![JsonRpcResp::new(id, sub_id).into()]
+            }
+            PubsubMethod::AccountUnsubscribe
+            | PubsubMethod::SignatureUnsubscribe
+            | PubsubMethod::SlotUnsubscribe => {
+                let sub_id = deser_unsubscribe_params(params).map_err(deser_err)?;
+                if !subs.remove(sub_id, method) {
+                    return Err(invalid_params("Invalid subscription id."));
+                }
+                vec![JsonRpcResp::new(id, true).into()]
+            }
+        })
+    }
+
+    async fn processed_tx_notifications(
+        &mut self,
+        ProcessedTxEvent {
+            signature,
+            slot,
+            err,
+            writable_accounts,
+        }: ProcessedTxEvent,
+        subs: &mut Subscriptions,
+    ) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let mut res = Vec::new();
+        let mut fulfilled_sig_subs = Vec::new();
+        for (sub_id, sub) in subs.subs.iter() {
+            match sub {
+                Subscription::Signature(s) if *s == signature => {
+                    res.push(signature_notification(*sub_id, slot, err.clone()));
+                    fulfilled_sig_subs.push(*sub_id);
+                }
+                Subscription::Account {
+                    key,
+                    encoding,
+                    data_slice,
+                } if writable_accounts.contains(key) => {
+                    // closed accounts are notified as empty system accounts, same as the actual RPC
+                    let account = self.bc.get_account(*key).await?.unwrap_or_default();
+                    // encoding errors, e.g. binary encoding of accounts too large for base58,
+                    // still notify with UiAccount::encode's placeholder data like the actual RPC
+                    let ui_account = match self
+                        .encode_account(key, account.clone(), *encoding, *data_slice)
+                        .await
+                    {
+                        Ok(ui_account) => ui_account,
+                        Err(_) => UiAccount::encode(key, &account, *encoding, None, *data_slice),
+                    };
+                    res.push(
+                        JsonRpcNotification::with_ctx(
+                            "accountNotification",
+                            *sub_id,
+                            ui_account,
+                            slot,
+                        )
+                        .into(),
+                    );
+                }
+                _ => (),
+            }
+        }
+        for sub_id in fulfilled_sig_subs {
+            subs.subs.remove(&sub_id);
+        }
+        Ok(res)
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A single JSON-RPC request and the response it got, one per line of a recording
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub request: Value,
+    pub response: Value,
+}
+
+/// Appends [`RecordedExchange`]s to a JSONL file
+#[derive(Debug)]
+pub(crate) struct RpcRecorder(BufWriter<File>);
+
+impl RpcRecorder {
+    /// Truncates the file if it already exists
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Flushes after every line so that the log is complete even if the test panics
+    pub fn record(&mut self, exchange: &RecordedExchange) -> io::Result<()> {
+        serde_json::to_writer(&mut self.0, exchange)?;
+        self.0.write_all(b"\n")?;
+        self.0.flush()
+    }
+}
+
+/// Reads a JSONL recording written by [`super::BanksRpcServer::start_recording`].
+///
+/// Blank lines are skipped
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<RecordedExchange>> {
+    let path = path.as_ref();
+    let mut res = Vec::new();
+    for (i, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let exchange = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {e}", path.display(), i + 1),
+            )
+        })?;
+        res.push(exchange);
+    }
+    Ok(res)
+}
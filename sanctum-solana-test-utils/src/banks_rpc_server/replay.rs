@@ -0,0 +1,179 @@
+use std::{
+    error::Error,
+    future::Future,
+    io,
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Buf, Bytes, Incoming},
+    server::conn::http1,
+    service::Service,
+    Request, Response,
+};
+use hyper_util::rt::TokioIo;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use super::{
+    json_rpc::{to_http_resp, JsonRpcErrResp},
+    read_recording, RecordedExchange,
+};
+
+#[derive(Deserialize)]
+struct ReplayReq {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Recorded responses to the same request, e.g. repeated `getSignatureStatuses` polls.
+///
+/// Requests are matched to recorded ones by method and parsed params, ignoring `id`
+#[derive(Debug)]
+struct ReplayQueue {
+    method: String,
+    params: Value,
+    responses: Vec<Value>,
+    next: usize,
+}
+
+impl ReplayQueue {
+    fn matches(&self, req: &ReplayReq) -> bool {
+        self.method == req.method && self.params == req.params
+    }
+
+    /// Replays responses in recorded order. `None` once exhausted
+    fn pop(&mut self) -> Option<&Value> {
+        let res = self.responses.get(self.next)?;
+        self.next += 1;
+        Some(res)
+    }
+}
+
+/// A HTTP server that answers JSON-RPC requests from a recording of a [`super::BanksRpcServer`]
+/// session instead of a `BanksClient`, for deterministic fixtures.
+///
+/// Requests that were not recorded, or were made more times than recorded,
+/// are responded to with a JSON-RPC error
+#[derive(Clone, Debug)]
+pub struct RpcReplayServer {
+    queues: Arc<Mutex<Vec<ReplayQueue>>>,
+}
+
+impl RpcReplayServer {
+    /// Invalid recorded requests are ignored
+    pub fn new(exchanges: impl IntoIterator<Item = RecordedExchange>) -> Self {
+        let mut queues: Vec<ReplayQueue> = Vec::new();
+        for RecordedExchange { request, response } in exchanges {
+            if let Ok(req) = serde_json::from_value::<ReplayReq>(request) {
+                match queues.iter_mut().find(|q| q.matches(&req)) {
+                    Some(q) => q.responses.push(response),
+                    None => queues.push(ReplayQueue {
+                        method: req.method,
+                        params: req.params,
+                        responses: vec![response],
+                        next: 0,
+                    }),
+                }
+            }
+        }
+        Self {
+            queues: Arc::new(Mutex::new(queues)),
+        }
+    }
+
+    pub fn from_recording(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(read_recording(path)?))
+    }
+
+    /// Spawns the HTTP server on `http://127.0.0.1:{random_unused_port}` (IPV4).
+    ///
+    /// Returns `(bound_port, RpcReplayServer join handle)`
+    pub async fn spawn_random_unused(
+        self,
+    ) -> (u16, JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>) {
+        let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = tcp_listener.local_addr().unwrap().port();
+        (port, self.spawn(tcp_listener))
+    }
+
+    /// Spawn the HTTP sever in the background
+    pub fn spawn(
+        self,
+        tcp_listener: TcpListener,
+    ) -> JoinHandle<Result<(), Box<dyn Error + Send + Sync>>> {
+        tokio::task::spawn(async move {
+            loop {
+                let (tcp_stream, _socket_addr) = tcp_listener.accept().await?;
+                let io = TokioIo::new(tcp_stream);
+                let this = self.clone();
+                tokio::task::spawn(async move {
+                    if let Err(err) = http1::Builder::new().serve_connection(io, this).await {
+                        eprintln!("Error serving connection: {:?}", err);
+                    }
+                });
+            }
+        })
+    }
+
+    /// The next recorded response with its `id` replaced by the request's
+    fn replay(&self, req: ReplayReq) -> Value {
+        let mut queues = self.queues.lock().unwrap();
+        let recorded = match queues.iter_mut().find(|q| q.matches(&req)) {
+            Some(q) => q.pop().cloned().ok_or_else(|| {
+                format!(
+                    "All {} recorded responses for {} with params {} already replayed",
+                    q.responses.len(),
+                    req.method,
+                    req.params
+                )
+            }),
+            None => Err(format!(
+                "No recorded response for {} with params {}",
+                req.method, req.params
+            )),
+        };
+        match recorded {
+            Ok(mut resp) => {
+                if let Some(id) = resp.get_mut("id") {
+                    *id = req.id.into();
+                }
+                resp
+            }
+            Err(message) => {
+                JsonRpcErrResp::new(req.id, jsonrpc_core::Error::invalid_params(message)).into()
+            }
+        }
+    }
+}
+
+impl Service<Request<Incoming>> for RpcReplayServer {
+    type Response = Response<Full<Bytes>>;
+
+    type Error = Box<dyn Error + Send + Sync>;
+
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, req: Request<Incoming>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let body = req.into_body().collect().await.ok().unwrap().to_bytes();
+            let resp = if let Ok(v) =
+                serde_json::from_reader::<_, Vec<ReplayReq>>(body.clone().reader())
+            {
+                Value::Array(v.into_iter().map(|r| this.replay(r)).collect())
+            } else if let Ok(r) = serde_json::from_reader(body.reader()) {
+                this.replay(r)
+            } else {
+                return Err("Invalid request".into());
+            };
+            Ok(to_http_resp(serde_json::to_vec(&resp).unwrap().into()))
+        })
+    }
+}
@@ -0,0 +1,74 @@
+//! Dumps accounts and programs from a RPC into `KeyedUiAccount` json and .so fixture files.
+//!
+//! ```sh
+//! capture-fixtures -u https://api.mainnet-beta.solana.com -o test-fixtures/my-pool \
+//!     --recipe stake-pool --seed <STAKE_POOL> --program SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use sanctum_solana_test_utils::capture::{CaptureRecipe, FixtureCapturer};
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser, Debug)]
+#[command(about = "Dump accounts and programs from a RPC into test fixture files")]
+struct Args {
+    #[arg(long, short, help = "RPC URL to fetch from")]
+    url: String,
+
+    #[arg(long, short, help = "Directory to write the fixture files to")]
+    out_dir: PathBuf,
+
+    #[arg(
+        long,
+        requires = "seed",
+        help = "Recipe to capture. Available: stake-pool"
+    )]
+    recipe: Option<CaptureRecipe>,
+
+    #[arg(long, requires = "recipe", help = "Pubkey to start the recipe from")]
+    seed: Option<Pubkey>,
+
+    #[arg(
+        long,
+        help = "Program to dump the .so file of. Can be specified multiple times"
+    )]
+    program: Vec<Pubkey>,
+
+    #[arg(help = "Accounts to capture")]
+    pubkeys: Vec<Pubkey>,
+}
+
+fn main() {
+    let Args {
+        url,
+        out_dir,
+        recipe,
+        seed,
+        program,
+        pubkeys,
+    } = Args::parse();
+    let capturer = FixtureCapturer::new(RpcClient::new(url), out_dir);
+    let mut written = Vec::new();
+    let res = (|| {
+        if !pubkeys.is_empty() {
+            written.extend(capturer.capture_accounts(&pubkeys)?);
+        }
+        if let (Some(recipe), Some(seed)) = (recipe, seed) {
+            written.extend(capturer.capture_recipe(recipe, seed)?);
+        }
+        for program_id in program {
+            written.push(capturer.capture_program(program_id)?);
+        }
+        Ok::<_, sanctum_solana_test_utils::capture::CaptureError>(())
+    })();
+    for path in written.iter() {
+        println!("{}", path.display());
+    }
+    if let Err(e) = res {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,281 @@
+//! Dump accounts and programs from a RPC into fixture files
+//! that [`crate::ExtendedProgramTest::add_test_fixtures_dir`] can load
+
+use std::{
+    error::Error,
+    fmt::Display,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use sanctum_spl_stake_pool_lib::{
+    deserialize_stake_pool_checked, deserialize_validator_list_checked, FindTransientStakeAccount,
+    FindTransientStakeAccountArgs, FindValidatorStakeAccount, FindValidatorStakeAccountArgs,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_program::pubkey::Pubkey;
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::{
+    account::Account,
+    bpf_loader, bpf_loader_deprecated,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+};
+
+use crate::KeyedUiAccount;
+
+/// Max number of accounts `getMultipleAccounts` accepts
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+#[derive(Debug)]
+pub enum CaptureError {
+    /// Boxed since [`ClientError`] is large
+    Rpc(Box<ClientError>),
+
+    AccountNotFound(Pubkey),
+
+    /// Account is not of the type the recipe expected
+    InvalidAccount {
+        addr: Pubkey,
+        reason: String,
+    },
+
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rpc(e) => write!(f, "RPC error: {e}"),
+            Self::AccountNotFound(addr) => write!(f, "Account {addr} not found"),
+            Self::InvalidAccount { addr, reason } => write!(f, "Invalid account {addr}: {reason}"),
+            Self::Io { path, source } => write!(f, "Failed to write {}: {source}", path.display()),
+        }
+    }
+}
+
+impl Error for CaptureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Rpc(e) => Some(e.as_ref()),
+            Self::Io { source, .. } => Some(source),
+            Self::AccountNotFound(_) | Self::InvalidAccount { .. } => None,
+        }
+    }
+}
+
+impl From<ClientError> for CaptureError {
+    fn from(e: ClientError) -> Self {
+        Self::Rpc(Box::new(e))
+    }
+}
+
+/// A set of accounts to capture, found starting from a seed pubkey
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CaptureRecipe {
+    /// Seed is the stake pool. Captures the stake pool, its validator list, reserve,
+    /// pool mint, manager fee account, and all its validator and transient stake accounts
+    /// and their vote accounts that exist
+    StakePool,
+}
+
+impl FromStr for CaptureRecipe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stake-pool" => Ok(Self::StakePool),
+            _ => Err(format!("Unknown recipe {s}. Available: stake-pool")),
+        }
+    }
+}
+
+/// Writes:
+/// - accounts to `<out_dir>/<pubkey>.json` in `solana account --output json` format,
+///   base64-encoded
+/// - programs to `<out_dir>/<program_id>.so`,
+///   which can be loaded with `add_upgradeable_program(program_id, "<program_id>", ..)`
+///   if `out_dir` is in the .so search path
+pub struct FixtureCapturer {
+    rpc: RpcClient,
+    out_dir: PathBuf,
+}
+
+impl FixtureCapturer {
+    pub fn new<P: AsRef<Path>>(rpc: RpcClient, out_dir: P) -> Self {
+        Self {
+            rpc,
+            out_dir: out_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    /// Fetches the accounts that exist out of `keys`
+    fn fetch_existing(&self, keys: &[Pubkey]) -> Result<Vec<Keyed<Account>>, CaptureError> {
+        let mut res = Vec::new();
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.rpc.get_multiple_accounts(chunk)?;
+            res.extend(chunk.iter().zip(accounts).filter_map(|(pubkey, account)| {
+                account.map(|account| Keyed {
+                    pubkey: *pubkey,
+                    account,
+                })
+            }));
+        }
+        Ok(res)
+    }
+
+    fn fetch(&self, addr: Pubkey) -> Result<Account, CaptureError> {
+        self.fetch_existing(&[addr])?
+            .pop()
+            .map(|k| k.account)
+            .ok_or(CaptureError::AccountNotFound(addr))
+    }
+
+    fn write_account(&self, keyed: &Keyed<Account>) -> Result<PathBuf, CaptureError> {
+        let path = self.out_dir.join(format!("{}.json", keyed.pubkey));
+        KeyedUiAccount::from_keyed_account(keyed, UiAccountEncoding::Base64)
+            .try_to_file(&path)
+            .map_err(|source| CaptureError::Io {
+                path: path.clone(),
+                source,
+            })?;
+        Ok(path)
+    }
+
+    fn write_all(&self, accounts: &[Keyed<Account>]) -> Result<Vec<PathBuf>, CaptureError> {
+        accounts.iter().map(|k| self.write_account(k)).collect()
+    }
+
+    /// Returns paths of the written files.
+    /// Errors without writing anything if any of the accounts does not exist
+    pub fn capture_accounts(&self, keys: &[Pubkey]) -> Result<Vec<PathBuf>, CaptureError> {
+        let accounts = self.fetch_existing(keys)?;
+        if let Some(missing) = keys
+            .iter()
+            .find(|k| !accounts.iter().any(|a| a.pubkey == **k))
+        {
+            return Err(CaptureError::AccountNotFound(*missing));
+        }
+        self.write_all(&accounts)
+    }
+
+    /// Returns paths of the written files
+    pub fn capture_recipe(
+        &self,
+        recipe: CaptureRecipe,
+        seed: Pubkey,
+    ) -> Result<Vec<PathBuf>, CaptureError> {
+        match recipe {
+            CaptureRecipe::StakePool => self.capture_stake_pool(seed),
+        }
+    }
+
+    /// See [`CaptureRecipe::StakePool`]
+    pub fn capture_stake_pool(&self, stake_pool: Pubkey) -> Result<Vec<PathBuf>, CaptureError> {
+        let invalid = |addr: Pubkey, e: &dyn Display| CaptureError::InvalidAccount {
+            addr,
+            reason: e.to_string(),
+        };
+        let pool_account = self.fetch(stake_pool)?;
+        let program_id = pool_account.owner;
+        let pool = deserialize_stake_pool_checked(&pool_account.data)
+            .map_err(|e| invalid(stake_pool, &e))?;
+        let list_account = self.fetch(pool.validator_list)?;
+        let list = deserialize_validator_list_checked(&list_account.data)
+            .map_err(|e| invalid(pool.validator_list, &e))?;
+
+        let mut accounts = vec![
+            Keyed {
+                pubkey: stake_pool,
+                account: pool_account,
+            },
+            Keyed {
+                pubkey: pool.validator_list,
+                account: list_account,
+            },
+        ];
+        for addr in [pool.reserve_stake, pool.pool_mint, pool.manager_fee_account] {
+            accounts.push(Keyed {
+                pubkey: addr,
+                account: self.fetch(addr)?,
+            });
+        }
+
+        let mut optional_keys = Vec::new();
+        for v in list.validators.iter() {
+            optional_keys.push(
+                FindValidatorStakeAccount::new(FindValidatorStakeAccountArgs {
+                    pool: stake_pool,
+                    vote: v.vote_account_address,
+                    seed: NonZeroU32::new(v.validator_seed_suffix),
+                })
+                .run_for_prog(&program_id)
+                .0,
+            );
+            optional_keys.push(
+                FindTransientStakeAccount::new(FindTransientStakeAccountArgs {
+                    pool: stake_pool,
+                    vote: v.vote_account_address,
+                    seed: v.transient_seed_suffix,
+                })
+                .run_for_prog(&program_id)
+                .0,
+            );
+            optional_keys.push(v.vote_account_address);
+        }
+        accounts.extend(self.fetch_existing(&optional_keys)?);
+
+        self.write_all(&accounts)
+    }
+
+    /// Writes the executable data of a BPF loader v2 or upgradeable program to
+    /// `<out_dir>/<program_id>.so` and returns its path
+    pub fn capture_program(&self, program_id: Pubkey) -> Result<PathBuf, CaptureError> {
+        let program = self.fetch(program_id)?;
+        let so_prog_data = if program.owner == bpf_loader_upgradeable::ID {
+            let programdata_address = match bincode::deserialize(&program.data) {
+                Ok(UpgradeableLoaderState::Program {
+                    programdata_address,
+                }) => programdata_address,
+                _ => {
+                    return Err(CaptureError::InvalidAccount {
+                        addr: program_id,
+                        reason: "Not an upgradeable program account".to_owned(),
+                    })
+                }
+            };
+            let mut programdata = self.fetch(programdata_address)?.data;
+            let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+            if programdata.len() < metadata_len {
+                return Err(CaptureError::InvalidAccount {
+                    addr: programdata_address,
+                    reason: "Program data account too small".to_owned(),
+                });
+            }
+            programdata.split_off(metadata_len)
+        } else if program.owner == bpf_loader::ID || program.owner == bpf_loader_deprecated::ID {
+            program.data
+        } else {
+            return Err(CaptureError::InvalidAccount {
+                addr: program_id,
+                reason: format!("Program owned by unsupported loader {}", program.owner),
+            });
+        };
+        let path = self.out_dir.join(format!("{program_id}.so"));
+        std::fs::create_dir_all(&self.out_dir)
+            .and_then(|_| std::fs::write(&path, so_prog_data))
+            .map_err(|source| CaptureError::Io {
+                path: path.clone(),
+                source,
+            })?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,224 @@
+use std::{error::Error, process::Output};
+
+use assert_cmd::Command;
+use data_encoding::BASE64;
+use serde::de::DeserializeOwned;
+use solana_program::hash::Hash;
+use solana_program_test::{BanksClient, BanksTransactionResultWithMetadata};
+use solana_sdk::{signature::Keypair, transaction::VersionedTransaction};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::banks_rpc_server::{BanksRpcServer, IndexedProgramTest};
+
+use super::TempCliConfig;
+
+type ServerJoinHandle = JoinHandle<Result<(), Box<dyn Error + Send + Sync>>>;
+
+/// Owns a started [`solana_program_test::ProgramTest`], a [`BanksRpcServer`] with websocket pubsub serving it,
+/// and a [`TempCliConfig`] pointing to the server with the `ProgramTest`'s payer as keypair.
+///
+/// The servers are stopped and the temp files deleted on drop.
+///
+/// ## Example
+///
+/// ```ignore
+/// let mut harness = CliTestHarness::start(pt).await;
+/// let res = harness
+///     .exec_b64_txs(harness.cmd("my-cli").arg("deposit").arg("1.0"))
+///     .await;
+/// ```
+pub struct CliTestHarness {
+    bc: BanksClient,
+    payer: Keypair,
+    last_blockhash: Hash,
+    server: BanksRpcServer,
+    rpc_port: u16,
+    pubsub_port: u16,
+    server_jh: ServerJoinHandle,
+    pubsub_jh: ServerJoinHandle,
+    config: TempCliConfig,
+}
+
+impl CliTestHarness {
+    /// Pass an [`IndexedProgramTest`] for the accounts added to it
+    /// to be returned by `getProgramAccounts`
+    pub async fn start(pt: impl Into<IndexedProgramTest>) -> Self {
+        let (bc, payer, last_blockhash, account_index) = pt.into().start().await;
+        let server = BanksRpcServer::new_with_account_index(bc.clone(), account_index);
+        let http_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let rpc_port = http_listener.local_addr().unwrap().port();
+        let pubsub_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let pubsub_port = pubsub_listener.local_addr().unwrap().port();
+        let server_jh = server.clone().spawn(http_listener);
+        let pubsub_jh = server.clone().spawn_pubsub(pubsub_listener);
+        let config = TempCliConfig::from_keypair_and_local_ports(&payer, rpc_port, pubsub_port);
+        Self {
+            bc,
+            payer,
+            last_blockhash,
+            server,
+            rpc_port,
+            pubsub_port,
+            server_jh,
+            pubsub_jh,
+            config,
+        }
+    }
+
+    /// `Command::cargo_bin(bin_name)` with `--config` set to the temp config file.
+    ///
+    /// ## Panics
+    /// If `bin_name` is not a binary of the workspace
+    pub fn cmd(&self, bin_name: &str) -> Command {
+        self.configure(Command::cargo_bin(bin_name).unwrap())
+    }
+
+    /// Appends `--config <temp config file>` to `cmd`'s args
+    pub fn configure(&self, mut cmd: Command) -> Command {
+        cmd.arg("--config").arg(self.config.config().path());
+        cmd
+    }
+
+    /// Runs `cmd`, returning its stdout.
+    ///
+    /// ## Panics
+    /// With stderr if `cmd` did not exit with status 0
+    pub fn run(&self, cmd: &mut Command) -> String {
+        let Output {
+            stdout,
+            status,
+            stderr,
+        } = cmd.output().unwrap();
+        assert!(status.success(), "{}", String::from_utf8_lossy(&stderr));
+        String::from_utf8(stdout).unwrap()
+    }
+
+    /// Runs `cmd`, deserializing its stdout as json
+    pub fn run_json<T: DeserializeOwned>(&self, cmd: &mut Command) -> T {
+        let stdout = self.run(cmd);
+        serde_json::from_str(&stdout)
+            .unwrap_or_else(|e| panic!("Invalid json output {e}. stdout:\n{stdout}"))
+    }
+
+    /// Runs `cmd` and processes the newline separated base64-encoded transactions
+    /// in its stdout in sequence with [`BanksRpcServer::process_transaction_with_metadata`],
+    /// so that they are visible to `getSignatureStatuses`, `getTransaction`,
+    /// pubsub subscribers and recording like transactions sent to the server
+    pub async fn exec_b64_txs(
+        &mut self,
+        cmd: &mut Command,
+    ) -> Vec<Result<BanksTransactionResultWithMetadata, Box<dyn Error + Send + Sync>>> {
+        let stdout = self.run(cmd);
+        let mut res = vec![];
+        for b64 in stdout.split('\n') {
+            if !b64.is_empty() {
+                res.push(self.exec_b64_tx(b64).await);
+            }
+        }
+        res
+    }
+
+    async fn exec_b64_tx(
+        &mut self,
+        b64: &str,
+    ) -> Result<BanksTransactionResultWithMetadata, Box<dyn Error + Send + Sync>> {
+        let tx: VersionedTransaction = bincode::deserialize(&BASE64.decode(b64.as_bytes())?)?;
+        self.server.process_transaction_with_metadata(tx).await
+    }
+
+    /// Transactions sent directly to this are not visible to the server's
+    /// `getSignatureStatuses`, `getTransaction` and pubsub subscribers
+    pub fn banks_client(&mut self) -> &mut BanksClient {
+        &mut self.bc
+    }
+
+    /// The keypair of the temp config
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    /// Blockhash returned by [`solana_program_test::ProgramTest::start`]
+    pub fn last_blockhash(&self) -> Hash {
+        self.last_blockhash
+    }
+
+    /// The running server. Configuring it, e.g. fault injection, applies to the spawned servers
+    pub fn server(&self) -> &BanksRpcServer {
+        &self.server
+    }
+
+    pub fn config(&self) -> &TempCliConfig {
+        &self.config
+    }
+
+    pub fn rpc_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.rpc_port)
+    }
+
+    pub fn websocket_url(&self) -> String {
+        format!("ws://127.0.0.1:{}", self.pubsub_port)
+    }
+}
+
+impl Drop for CliTestHarness {
+    fn drop(&mut self) {
+        self.server_jh.abort();
+        self.pubsub_jh.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sanctum_solana_cli_utils::ConfigWrapper;
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use solana_program::system_instruction;
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{signer::Signer, transaction::Transaction};
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn harness_basic() {
+        let mut harness = CliTestHarness::start(ProgramTest::default()).await;
+
+        let config_path = harness
+            .config()
+            .config()
+            .path()
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let config = ConfigWrapper::parse_from_path(&config_path).unwrap();
+        assert_eq!(config.as_ref().json_rpc_url, harness.rpc_url());
+        assert_eq!(config.as_ref().websocket_url, harness.websocket_url());
+        assert_eq!(config.signer().pubkey(), harness.payer().pubkey());
+
+        // `sh -c <script> --config <path>` sets $0 = --config, $1 = <path>
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(r#"printf '["%s", "%s"]' "$0" "$1""#);
+        let args: Vec<String> = harness.run_json(&mut harness.configure(cmd));
+        assert_eq!(args, ["--config".to_owned(), config_path]);
+
+        let payer = harness.payer().pubkey();
+        let mut tx = Transaction::new_with_payer(
+            &[system_instruction::transfer(&payer, &payer, 1)],
+            Some(&payer),
+        );
+        tx.sign(&[harness.payer()], harness.last_blockhash());
+        let b64 = BASE64.encode(&bincode::serialize(&tx).unwrap());
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(format!("echo {b64}"));
+        let mut cmd = harness.configure(cmd);
+        let res = harness.exec_b64_txs(&mut cmd).await;
+        assert_eq!(res.len(), 1);
+        res[0].as_ref().unwrap().result.as_ref().unwrap();
+
+        let statuses = RpcClient::new(harness.rpc_url())
+            .get_signature_statuses(&[tx.signatures[0]])
+            .await
+            .unwrap()
+            .value;
+        assert!(statuses[0].as_ref().unwrap().err.is_none());
+    }
+}
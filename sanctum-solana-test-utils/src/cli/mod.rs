@@ -3,3 +3,9 @@ mod temp_cli_config;
 
 pub use assert_cmd::*;
 pub use temp_cli_config::*;
+
+#[cfg(feature = "banks-rpc-server")]
+mod harness;
+
+#[cfg(feature = "banks-rpc-server")]
+pub use harness::*;
@@ -18,14 +18,23 @@ pub struct TempCliConfig {
 }
 
 impl TempCliConfig {
+    /// `websocket_url` is left empty. Use [`Self::from_keypair_and_urls`] to set it
     pub fn from_keypair_and_rpc_url(keypair: &Keypair, json_rpc_url: String) -> Self {
+        Self::from_keypair_and_urls(keypair, json_rpc_url, "".to_owned())
+    }
+
+    pub fn from_keypair_and_urls(
+        keypair: &Keypair,
+        json_rpc_url: String,
+        websocket_url: String,
+    ) -> Self {
         let keypair = temp_keypair_file(keypair);
         let config = NamedTempFile::new().unwrap();
         serde_yaml::to_writer(
             config.as_file(),
             &Config {
                 json_rpc_url,
-                websocket_url: "".to_owned(),
+                websocket_url,
                 keypair_path: keypair.path().to_str().unwrap().to_owned(),
                 address_labels: HashMap::new(),
                 commitment: "confirmed".to_owned(),
@@ -40,6 +49,20 @@ impl TempCliConfig {
         Self::from_keypair_and_rpc_url(keypair, format!("http://127.0.0.1:{port}"))
     }
 
+    /// Config will point to `http://127.0.0.1:{rpc_port}` and `ws://127.0.0.1:{pubsub_port}`,
+    /// e.g. the ports returned by `BanksRpcServer::spawn_random_unused_with_pubsub()`
+    pub fn from_keypair_and_local_ports(
+        keypair: &Keypair,
+        rpc_port: u16,
+        pubsub_port: u16,
+    ) -> Self {
+        Self::from_keypair_and_urls(
+            keypair,
+            format!("http://127.0.0.1:{rpc_port}"),
+            format!("ws://127.0.0.1:{pubsub_port}"),
+        )
+    }
+
     // Access keypair and config via getters to ensure this struct is never
     // destructured, since destructuring can drop unused fields => delete file
 
@@ -71,4 +94,13 @@ mod tests {
         );
         assert_eq!(config.signer().pubkey(), kp.pubkey());
     }
+
+    #[test]
+    fn temp_cli_config_local_ports() {
+        let kp = Keypair::new();
+        let tcc = TempCliConfig::from_keypair_and_local_ports(&kp, 12321, 23432);
+        let config = ConfigWrapper::parse_from_path(tcc.config().path().to_str().unwrap()).unwrap();
+        assert_eq!(config.as_ref().json_rpc_url, "http://127.0.0.1:12321");
+        assert_eq!(config.as_ref().websocket_url, "ws://127.0.0.1:23432");
+    }
 }
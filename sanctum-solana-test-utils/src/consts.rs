@@ -1,3 +1,4 @@
+use solana_program::{pubkey, pubkey::Pubkey};
 use solana_sdk::rent::Rent;
 
 /// These might change in the future
@@ -13,3 +14,62 @@ pub const fn est_rent_exempt_lamports(account_data_len: usize) -> u64 {
 pub fn default_rent_exempt_lamports(account_data_len: usize) -> u64 {
     Rent::default().minimum_balance(account_data_len)
 }
+
+pub const TOKENKEG_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Size of a tokenkeg token account, and of the base token account of token-2022 accounts
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Size of a tokenkeg mint, and of the base mint of token-2022 mints
+pub const TOKEN_MINT_LEN: usize = 82;
+
+/// Byte offset of the u64 LE `amount` field in token accounts
+pub const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// `AccountType::Account` discriminant that follows the base token account
+/// in token-2022 accounts with extensions
+pub const TOKEN_2022_ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+#[cfg(all(test, any(feature = "token", feature = "token-2022")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "token")]
+    #[test]
+    fn tokenkeg_consts_match_spl_token() {
+        use solana_program::program_pack::Pack;
+
+        assert_eq!(TOKENKEG_PROGRAM_ID, spl_token::ID);
+        assert_eq!(TOKEN_ACCOUNT_LEN, spl_token::state::Account::LEN);
+        assert_eq!(TOKEN_MINT_LEN, spl_token::state::Mint::LEN);
+
+        let mut data = [0u8; TOKEN_ACCOUNT_LEN];
+        spl_token::state::Account {
+            amount: 12_345,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        assert_eq!(
+            data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8],
+            12_345u64.to_le_bytes()
+        );
+    }
+
+    #[cfg(feature = "token-2022")]
+    #[test]
+    fn token_2022_consts_match_spl_token_2022() {
+        use solana_program::program_pack::Pack;
+        use spl_token_2022::extension::AccountType;
+
+        assert_eq!(TOKEN_2022_PROGRAM_ID, spl_token_2022::ID);
+        assert_eq!(TOKEN_ACCOUNT_LEN, spl_token_2022::state::Account::LEN);
+        assert_eq!(TOKEN_MINT_LEN, spl_token_2022::state::Mint::LEN);
+        assert_eq!(TOKEN_2022_ACCOUNT_TYPE_ACCOUNT, AccountType::Account as u8);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{test_fixtures_dir, ExecOutput};
+
+/// Set this env var to any value other than `0` or `false` to make
+/// [`CuBaseline::record`] rewrite baselines instead of checking against them
+pub const UPDATE_CU_BASELINES_ENV_VAR: &str = "UPDATE_CU_BASELINES";
+
+/// Serializes read-modify-writes of baseline files by tests running in parallel.
+/// Only works within a single process, see [`CuBaseline::record`]
+static BASELINE_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// What to do when a measurement exceeds its baseline by more than the tolerance,
+/// or has no baseline
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CuRegressionAction {
+    #[default]
+    Fail,
+    Warn,
+}
+
+/// A JSON file of `{ name: compute_units }` baselines.
+///
+/// ## Example
+///
+/// ```ignore
+/// let out = bc.try_exec(tx).await.unwrap();
+/// CuBaseline::new("cu-baselines/my-program.json")
+///     .tolerance_bps(200)
+///     .record_exec("swap", &out);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CuBaseline {
+    path: PathBuf,
+    tolerance_bps: u64,
+    on_regression: CuRegressionAction,
+    update: bool,
+}
+
+impl CuBaseline {
+    /// `relative_path` is relative to [`test_fixtures_dir`].
+    ///
+    /// Defaults to 0 tolerance, failing on regression,
+    /// and updating if [`UPDATE_CU_BASELINES_ENV_VAR`] is set
+    pub fn new<P: AsRef<Path>>(relative_path: P) -> Self {
+        Self::from_path(test_fixtures_dir().join(relative_path))
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let update = std::env::var(UPDATE_CU_BASELINES_ENV_VAR)
+            .map(|v| !matches!(v.as_str(), "" | "0" | "false"))
+            .unwrap_or(false);
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tolerance_bps: 0,
+            on_regression: CuRegressionAction::default(),
+            update,
+        }
+    }
+
+    /// Measurements may exceed their baseline by up to this many bps of the baseline
+    pub fn tolerance_bps(mut self, tolerance_bps: u64) -> Self {
+        self.tolerance_bps = tolerance_bps;
+        self
+    }
+
+    pub fn on_regression(mut self, on_regression: CuRegressionAction) -> Self {
+        self.on_regression = on_regression;
+        self
+    }
+
+    /// Overrides [`UPDATE_CU_BASELINES_ENV_VAR`]
+    pub fn update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns an empty map if the file does not exist yet
+    pub fn load(&self) -> BTreeMap<String, u64> {
+        match File::open(&self.path) {
+            Ok(f) => serde_json::from_reader(BufReader::new(f)).unwrap_or_else(|e| {
+                panic!("Invalid CU baseline file {}: {e}", self.path.display())
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => panic!(
+                "Failed to read CU baseline file {}: {e}",
+                self.path.display()
+            ),
+        }
+    }
+
+    /// Highest measurement that does not count as a regression of `baseline`
+    pub fn max_allowed(&self, baseline: u64) -> u64 {
+        baseline.saturating_add(baseline.saturating_mul(self.tolerance_bps) / 10_000)
+    }
+
+    /// Checks `compute_units` against the baseline of `name`,
+    /// or writes it as the new baseline in update mode.
+    ///
+    /// NB: updates are only serialized within a process. Each test binary runs as a separate
+    /// process, so concurrently updating the same baseline file from multiple test binaries
+    /// may lose some of the updates. Files are replaced atomically so checks never see
+    /// a partially written file.
+    ///
+    /// ## Panics
+    /// - on regression or missing baseline if [`CuRegressionAction::Fail`]
+    /// - if the baseline file cannot be read or written
+    pub fn record(&self, name: &str, compute_units: u64) {
+        let _guard = BASELINE_FILE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if self.update {
+            self.write(name, compute_units);
+            return;
+        }
+        let baselines = self.load();
+        let msg = match baselines.get(name) {
+            None => format!(
+                "No CU baseline for {name} in {}. Rerun with {UPDATE_CU_BASELINES_ENV_VAR}=1 to record it",
+                self.path.display()
+            ),
+            Some(baseline) if compute_units > self.max_allowed(*baseline) => format!(
+                "{name} consumed {compute_units} CUs, regressed from baseline {baseline} beyond tolerance of {} bps. Rerun with {UPDATE_CU_BASELINES_ENV_VAR}=1 to accept",
+                self.tolerance_bps
+            ),
+            Some(baseline) => {
+                if compute_units < *baseline {
+                    eprintln!(
+                        "{name} consumed {compute_units} CUs, improved from baseline {baseline}"
+                    );
+                }
+                return;
+            }
+        };
+        match self.on_regression {
+            CuRegressionAction::Fail => panic!("{msg}"),
+            CuRegressionAction::Warn => eprintln!("WARNING: {msg}"),
+        }
+    }
+
+    /// [`Self::record`] the total compute units consumed by a transaction
+    pub fn record_exec<T>(&self, name: &str, output: &ExecOutput<T>) {
+        self.record(name, output.compute_units_consumed)
+    }
+
+    /// Writes to a temp file in the same dir then renames it over the baseline file
+    /// so that other processes never read a partially written file
+    fn write(&self, name: &str, compute_units: u64) {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let mut baselines = self.load();
+        baselines.insert(name.to_owned(), compute_units);
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(format!(".{}.tmp", std::process::id()));
+        let mut f = File::create(&tmp_path).unwrap();
+        serde_json::to_writer_pretty(&mut f, &baselines).unwrap();
+        f.write_all(b"\n").unwrap();
+        drop(f);
+        std::fs::rename(&tmp_path, &self.path).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline_in(dir: &tempfile::TempDir) -> CuBaseline {
+        CuBaseline::from_path(dir.path().join("nested").join("baseline.json"))
+    }
+
+    #[test]
+    fn update_then_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = baseline_in(&dir).update(true);
+        baseline.record("a", 1000);
+        baseline.record("b", 2000);
+        assert_eq!(
+            baseline.load(),
+            BTreeMap::from([("a".to_owned(), 1000), ("b".to_owned(), 2000)])
+        );
+        // temp files are renamed over the baseline file
+        assert_eq!(
+            std::fs::read_dir(baseline.path().parent().unwrap())
+                .unwrap()
+                .count(),
+            1
+        );
+
+        let baseline = baseline.update(false).tolerance_bps(100);
+        baseline.record("a", 1010);
+        baseline.record("b", 1500);
+        baseline
+            .on_regression(CuRegressionAction::Warn)
+            .record("a", 1011);
+    }
+
+    #[test]
+    #[should_panic(expected = "regressed from baseline 1000")]
+    fn regression_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = baseline_in(&dir).update(true);
+        baseline.record("a", 1000);
+        baseline.update(false).tolerance_bps(100).record("a", 1011);
+    }
+
+    #[test]
+    #[should_panic(expected = "No CU baseline for a")]
+    fn missing_baseline_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        baseline_in(&dir).update(false).record("a", 1000);
+    }
+}
@@ -0,0 +1,466 @@
+use std::fmt::Display;
+
+use solana_program_test::{BanksTransactionResultWithMetadata, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transaction_context::TransactionReturnData,
+};
+
+use crate::{
+    AccountsDiff, AccountsSnapshot, ExtendedProgramTest, ExtendedProgramTestContext, ParsedLogs,
+    ProgramInvocation,
+};
+
+/// What a transaction did on one side of a [`DifferentialHarness`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxOutcome {
+    pub result: Result<(), TransactionError>,
+
+    /// `None` if the transaction failed or set no return data
+    pub return_data: Option<TransactionReturnData>,
+
+    pub compute_units_consumed: u64,
+
+    pub log_messages: Vec<String>,
+}
+
+impl TxOutcome {
+    /// Parsed logs without consumed compute units,
+    /// which almost always change when a program is upgraded
+    fn comparable_logs(&self) -> ParsedLogs {
+        fn strip_cus(invocation: &mut ProgramInvocation) {
+            invocation.compute_units_consumed = None;
+            invocation.inner.iter_mut().for_each(strip_cus);
+        }
+        let mut logs = ParsedLogs::parse(&self.log_messages);
+        logs.instructions.iter_mut().for_each(strip_cus);
+        logs
+    }
+}
+
+/// An aspect in which the candidate's execution of a transaction
+/// differed from the baseline's
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Divergence {
+    Result,
+    ReturnData,
+
+    /// Only checked if enabled with [`DifferentialHarness::compare_logs`].
+    /// Consumed compute units are not compared
+    Logs,
+
+    /// States of the compared accounts after the transaction
+    Accounts,
+}
+
+/// The outcomes of a transaction executed against both sides of a [`DifferentialHarness`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxComparison {
+    pub name: String,
+    pub baseline: TxOutcome,
+    pub candidate: TxOutcome,
+
+    /// Diff going from the baseline's to the candidate's state of the compared accounts
+    /// after the transaction
+    pub accounts: AccountsDiff,
+
+    pub divergences: Vec<Divergence>,
+}
+
+impl TxComparison {
+    pub fn is_divergent(&self) -> bool {
+        !self.divergences.is_empty()
+    }
+}
+
+impl Display for TxComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.is_divergent() {
+            return writeln!(f, "{}: equivalent", self.name);
+        }
+        writeln!(f, "{}: diverged in {:?}", self.name, self.divergences)?;
+        for d in self.divergences.iter() {
+            match d {
+                Divergence::Result => writeln!(
+                    f,
+                    "  result: baseline {:?}, candidate {:?}",
+                    self.baseline.result, self.candidate.result
+                )?,
+                Divergence::ReturnData => writeln!(
+                    f,
+                    "  return data: baseline {:?}, candidate {:?}",
+                    self.baseline.return_data, self.candidate.return_data
+                )?,
+                Divergence::Logs => write!(
+                    f,
+                    "  baseline logs:\n{}  candidate logs:\n{}",
+                    self.baseline.comparable_logs(),
+                    self.candidate.comparable_logs()
+                )?,
+                Divergence::Accounts => writeln!(f, "  accounts:\n{}", self.accounts)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Comparisons of a corpus of transactions, in execution order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DifferentialReport(pub Vec<TxComparison>);
+
+impl DifferentialReport {
+    pub fn divergent(&self) -> impl Iterator<Item = &TxComparison> {
+        self.0.iter().filter(|c| c.is_divergent())
+    }
+
+    pub fn is_equivalent(&self) -> bool {
+        self.divergent().next().is_none()
+    }
+
+    /// Panics with the divergent transactions if there are any
+    pub fn assert_equivalent(&self) {
+        assert!(self.is_equivalent(), "{self}");
+    }
+}
+
+impl Display for DifferentialReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n_divergent = self.divergent().count();
+        writeln!(f, "{n_divergent} of {} transactions diverged", self.0.len())?;
+        self.divergent().try_for_each(|c| write!(f, "{c}"))
+    }
+}
+
+/// A named transaction of a corpus. The first signer is the fee payer
+#[derive(Clone, Debug)]
+pub struct CorpusTx<'a> {
+    pub name: String,
+    pub ixs: Vec<Instruction>,
+    pub signers: Vec<&'a Keypair>,
+}
+
+/// Runs the same sequence of transactions against two [`ProgramTest`]s,
+/// e.g. loaded with the current and upgraded versions of a program,
+/// and compares what they did.
+///
+/// Transactions are signed by the given keypairs instead of the [`ProgramTest`]s' random payers,
+/// so fee payers and other signers must be funded identically on both sides,
+/// e.g. with [`crate::TestWalletFactory`].
+///
+/// ## Example
+///
+/// ```ignore
+/// let mut harness = DifferentialHarness::start_with_programs(
+///     || setup_pt(),
+///     my_program::ID,
+///     None,
+///     "my_program_v1",
+///     "my_program_v2",
+/// )
+/// .await
+/// .with_keys([pool, alice.ata(&mint)])
+/// .compare_logs(true);
+/// harness.run(&corpus).await.assert_equivalent();
+/// ```
+pub struct DifferentialHarness {
+    pub baseline: ProgramTestContext,
+    pub candidate: ProgramTestContext,
+    keys: Vec<Pubkey>,
+    compare_logs: bool,
+}
+
+impl DifferentialHarness {
+    pub async fn start(baseline: ProgramTest, candidate: ProgramTest) -> Self {
+        Self {
+            baseline: baseline.start_with_context().await,
+            candidate: candidate.start_with_context().await,
+            keys: Vec::new(),
+            compare_logs: false,
+        }
+    }
+
+    /// Starts two [`ProgramTest`]s created by `pt_fn` with `program_id` added as an
+    /// upgradeable program from `{baseline_program_name}.so` and `{candidate_program_name}.so`
+    /// respectively
+    pub async fn start_with_programs<F: Fn() -> ProgramTest>(
+        pt_fn: F,
+        program_id: Pubkey,
+        upgrade_auth_addr: Option<Pubkey>,
+        baseline_program_name: &str,
+        candidate_program_name: &str,
+    ) -> Self {
+        let [baseline, candidate] =
+            [baseline_program_name, candidate_program_name].map(|program_name| {
+                pt_fn().add_upgradeable_program(program_id, program_name, upgrade_auth_addr, 0)
+            });
+        Self::start(baseline, candidate).await
+    }
+
+    /// Compare the states of `keys` after every transaction
+    pub fn with_keys(mut self, keys: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.keys.extend(keys);
+        self
+    }
+
+    /// Whether to also compare log messages, excluding consumed compute units.
+    /// Defaults to false
+    pub fn compare_logs(mut self, compare_logs: bool) -> Self {
+        self.compare_logs = compare_logs;
+        self
+    }
+
+    /// Makes subsequent transactions use a new blockhash on both sides,
+    /// e.g. to send a transaction identical to an earlier one
+    pub async fn refresh_blockhashes(&mut self) {
+        self.baseline.refresh_blockhash().await;
+        self.candidate.refresh_blockhash().await;
+    }
+
+    /// Executes the transaction on the baseline then on the candidate and compares them
+    ///
+    /// ## Panics
+    /// If `signers` is empty
+    pub async fn exec(
+        &mut self,
+        name: &str,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TxComparison {
+        let payer = signers
+            .first()
+            .unwrap_or_else(|| panic!("{name}: no fee payer"))
+            .pubkey();
+        let (baseline, baseline_accounts) =
+            exec_on(&mut self.baseline, &self.keys, ixs, &payer, signers).await;
+        let (candidate, candidate_accounts) =
+            exec_on(&mut self.candidate, &self.keys, ixs, &payer, signers).await;
+        let accounts = baseline_accounts.diff(&candidate_accounts);
+
+        let mut divergences = Vec::new();
+        if baseline.result != candidate.result {
+            divergences.push(Divergence::Result);
+        }
+        if baseline.return_data != candidate.return_data {
+            divergences.push(Divergence::ReturnData);
+        }
+        if self.compare_logs && baseline.comparable_logs() != candidate.comparable_logs() {
+            divergences.push(Divergence::Logs);
+        }
+        if !accounts.0.is_empty() {
+            divergences.push(Divergence::Accounts);
+        }
+        TxComparison {
+            name: name.to_owned(),
+            baseline,
+            candidate,
+            accounts,
+            divergences,
+        }
+    }
+
+    /// [`Self::exec`] for each transaction of `corpus`, in order.
+    /// Continues past divergent transactions
+    pub async fn run(&mut self, corpus: &[CorpusTx<'_>]) -> DifferentialReport {
+        let mut res = Vec::with_capacity(corpus.len());
+        for CorpusTx { name, ixs, signers } in corpus {
+            res.push(self.exec(name, ixs, signers).await);
+        }
+        DifferentialReport(res)
+    }
+}
+
+async fn exec_on(
+    ctx: &mut ProgramTestContext,
+    keys: &[Pubkey],
+    ixs: &[Instruction],
+    payer: &Pubkey,
+    signers: &[&Keypair],
+) -> (TxOutcome, AccountsSnapshot) {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(ixs, Some(payer), signers, blockhash);
+    let BanksTransactionResultWithMetadata { result, metadata } = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    let (compute_units_consumed, log_messages, return_data) = metadata
+        .map(|m| (m.compute_units_consumed, m.log_messages, m.return_data))
+        .unwrap_or_default();
+    let outcome = TxOutcome {
+        return_data: return_data.filter(|_| result.is_ok()),
+        result,
+        compute_units_consumed,
+        log_messages,
+    };
+    let accounts = AccountsSnapshot::fetch(&mut ctx.banks_client, keys.iter().copied())
+        .await
+        .unwrap();
+    (outcome, accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, instruction::AccountMeta,
+        program::set_return_data, system_instruction,
+    };
+    use solana_program_test::processor;
+    use solana_sdk::account::Account;
+
+    use crate::{test_keypair, TestWalletFactory};
+
+    use super::*;
+
+    const PROGRAM_ID: Pubkey =
+        solana_program::pubkey!("DiffTest11111111111111111111111111111111111");
+
+    fn write_version(accounts: &[AccountInfo], version: u8) -> ProgramResult {
+        accounts[0].try_borrow_mut_data()?[0] = version;
+        set_return_data(&[version]);
+        Ok(())
+    }
+
+    fn process_v1(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+        write_version(accounts, 1)
+    }
+
+    fn process_v2(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+        write_version(accounts, 2)
+    }
+
+    fn version_corpus_tx(payer: &Keypair, state: Pubkey) -> CorpusTx<'_> {
+        CorpusTx {
+            name: "write_version".to_owned(),
+            ixs: vec![Instruction::new_with_bytes(
+                PROGRAM_ID,
+                &[],
+                vec![AccountMeta::new(state, false)],
+            )],
+            signers: vec![payer],
+        }
+    }
+
+    fn with_state_account(pt: ProgramTest, state: Pubkey) -> ProgramTest {
+        pt.add_account_chained(
+            state,
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![0],
+                owner: PROGRAM_ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn detects_program_behaviour_change() {
+        const TEST_NAME: &str = "detects_program_behaviour_change";
+        let state = Pubkey::new_unique();
+        let mut baseline = ProgramTest::default();
+        baseline.prefer_bpf(false);
+        baseline.add_program("differential_v1", PROGRAM_ID, processor!(process_v1));
+        let mut candidate = ProgramTest::default();
+        candidate.prefer_bpf(false);
+        candidate.add_program("differential_v2", PROGRAM_ID, processor!(process_v2));
+        let [(baseline, alice), (candidate, _)] = [baseline, candidate].map(|pt| {
+            TestWalletFactory::new(TEST_NAME).create(with_state_account(pt, state), "alice")
+        });
+        let mut harness = DifferentialHarness::start(baseline, candidate)
+            .await
+            .with_keys([state]);
+
+        let report = harness
+            .run(&[version_corpus_tx(&alice.keypair, state)])
+            .await;
+
+        let cmp = &report.0[0];
+        assert_eq!(
+            cmp.divergences,
+            [Divergence::ReturnData, Divergence::Accounts]
+        );
+        assert_eq!(cmp.baseline.return_data.as_ref().unwrap().data, [1]);
+        assert_eq!(cmp.candidate.return_data.as_ref().unwrap().data, [2]);
+        assert!(cmp.accounts.is_changed(&state));
+    }
+
+    /// Requires `differential_v1.so` and `differential_v2.so` in one of
+    /// [`solana_program_test::find_file`]'s search paths, e.g. `tests/fixtures`:
+    /// builds of a program with id [`PROGRAM_ID`] that behaves like [`process_v1`]
+    /// and [`process_v2`] respectively. `*.so` files are not checked in
+    #[tokio::test]
+    #[ignore = "requires differential_v1.so and differential_v2.so program builds"]
+    async fn start_with_programs_detects_program_behaviour_change() {
+        const TEST_NAME: &str = "start_with_programs_detects_program_behaviour_change";
+        let state = Pubkey::new_unique();
+        let mut harness = DifferentialHarness::start_with_programs(
+            || {
+                TestWalletFactory::new(TEST_NAME)
+                    .create(with_state_account(ProgramTest::default(), state), "alice")
+                    .0
+            },
+            PROGRAM_ID,
+            None,
+            "differential_v1",
+            "differential_v2",
+        )
+        .await
+        .with_keys([state]);
+
+        let alice = test_keypair(TEST_NAME, 0);
+        let report = harness.run(&[version_corpus_tx(&alice, state)]).await;
+
+        assert_eq!(
+            report.0[0].divergences,
+            [Divergence::ReturnData, Divergence::Accounts]
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_divergence() {
+        let recipient = Pubkey::new_unique();
+        let pts = [1_000_000_000, 10_000_000].map(|lamports| {
+            TestWalletFactory::new("detects_divergence")
+                .with_lamports(lamports)
+                .create(ProgramTest::default(), "alice")
+        });
+        let [(baseline, alice), (candidate, _)] = pts;
+        let mut harness = DifferentialHarness::start(baseline, candidate)
+            .await
+            .with_keys([recipient])
+            .compare_logs(true);
+
+        let small = system_instruction::transfer(&alice.pubkey(), &recipient, 1_000_000);
+        let large = system_instruction::transfer(&alice.pubkey(), &recipient, 100_000_000);
+        let report = harness
+            .run(&[
+                CorpusTx {
+                    name: "small".to_owned(),
+                    ixs: vec![small],
+                    signers: vec![&alice.keypair],
+                },
+                CorpusTx {
+                    name: "large".to_owned(),
+                    ixs: vec![large],
+                    signers: vec![&alice.keypair],
+                },
+            ])
+            .await;
+
+        assert!(!report.0[0].is_divergent(), "{}", report.0[0]);
+        let large = &report.0[1];
+        assert_eq!(
+            large.divergences,
+            [Divergence::Result, Divergence::Logs, Divergence::Accounts]
+        );
+        assert!(large.accounts.is_changed(&recipient));
+        assert!(!report.is_equivalent());
+        assert!(report
+            .to_string()
+            .starts_with("1 of 2 transactions diverged"));
+    }
+}
@@ -8,37 +8,16 @@ use solana_sdk::{
     bpf_loader_upgradeable::{self, UpgradeableLoaderState},
 };
 
-use crate::{default_rent_exempt_lamports, load_program_so, test_fixtures_dir, KeyedUiAccount};
+use crate::{
+    default_rent_exempt_lamports, load_fixtures_dir, load_program_so, test_fixtures_dir,
+    KeyedUiAccount,
+};
 
-/// For nice method syntax on [`ProgramTest`]
-pub trait ExtendedProgramTest {
+/// For nice method syntax on [`ProgramTest`].
+///
+/// Implementors only need to implement [`Self::add_account_chained`]
+pub trait ExtendedProgramTest: Sized {
     fn add_account_chained(self, address: Pubkey, account: Account) -> Self;
-    fn add_keyed_account(self, keyed_account: Keyed<Account>) -> Self;
-    fn add_keyed_ui_account(self, keyed_ui_account: KeyedUiAccount) -> Self;
-    fn add_account_from_file<P: AsRef<Path>>(self, json_file_path: P) -> Self;
-    fn add_test_fixtures_account<P: AsRef<Path>>(self, relative_json_file_path: P) -> Self;
-    fn add_system_account(self, address: Pubkey, lamports: u64) -> Self;
-
-    /// Adds a compiled BPF program as an upgradeable program.
-    /// Like [ProgramTest::add_program], the program_name must match `{program_name}.so`
-    ///
-    /// Works the same way as [ProgramTest::add_program], except:
-    /// - sets the program's owner to BpfLoaderUpgradeable instead of BpfLoader
-    /// - always equivalent to prefer_bpf = true, only works with compiled .so files
-    fn add_upgradeable_program(
-        self,
-        program_id: Pubkey,
-        program_name: &str,
-        upgrade_auth_addr: Option<Pubkey>,
-        last_upgrade_slot: u64,
-    ) -> Self;
-}
-
-impl ExtendedProgramTest for ProgramTest {
-    fn add_account_chained(mut self, address: Pubkey, account: Account) -> Self {
-        self.add_account(address, account);
-        self
-    }
 
     fn add_keyed_account(self, Keyed { pubkey, account }: Keyed<Account>) -> Self {
         self.add_account_chained(pubkey, account)
@@ -70,22 +49,43 @@ impl ExtendedProgramTest for ProgramTest {
         )
     }
 
+    /// Adds a compiled BPF program as an upgradeable program.
+    /// Like [ProgramTest::add_program], the program_name must match `{program_name}.so`
+    ///
+    /// Works the same way as [ProgramTest::add_program], except:
+    /// - sets the program's owner to BpfLoaderUpgradeable instead of BpfLoader
+    /// - always equivalent to prefer_bpf = true, only works with compiled .so files
     fn add_upgradeable_program(
-        mut self,
+        self,
         program_id: Pubkey,
         program_name: &str,
         upgrade_auth_addr: Option<Pubkey>,
         last_upgrade_slot: u64,
+    ) -> Self {
+        self.add_upgradeable_program_from_data(
+            program_id,
+            &load_program_so(program_name),
+            upgrade_auth_addr,
+            last_upgrade_slot,
+        )
+    }
+
+    /// [`Self::add_upgradeable_program`] with the contents of a .so file
+    fn add_upgradeable_program_from_data(
+        mut self,
+        program_id: Pubkey,
+        so_prog_data: &[u8],
+        upgrade_auth_addr: Option<Pubkey>,
+        last_upgrade_slot: u64,
     ) -> Self {
         let (prog_data_addr, _bump) =
             Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
-        let so_prog_data = load_program_so(program_name);
 
         // add program account
         let mut prog_acc_data = Vec::with_capacity(UpgradeableLoaderState::size_of_program());
         prog_acc_data.write_all(&2u32.to_le_bytes()).unwrap();
         prog_acc_data.write_all(prog_data_addr.as_ref()).unwrap();
-        self.add_account(
+        self = self.add_account_chained(
             program_id,
             Account {
                 lamports: default_rent_exempt_lamports(UpgradeableLoaderState::size_of_program()),
@@ -113,8 +113,8 @@ impl ExtendedProgramTest for ProgramTest {
                 prog_data_acc_data.write_all(&[0u8; 33]).unwrap();
             }
         }
-        prog_data_acc_data.write_all(&so_prog_data).unwrap();
-        self.add_account(
+        prog_data_acc_data.write_all(so_prog_data).unwrap();
+        self.add_account_chained(
             prog_data_addr,
             Account {
                 lamports: default_rent_exempt_lamports(prog_data_acc_data.len()),
@@ -123,8 +123,35 @@ impl ExtendedProgramTest for ProgramTest {
                 executable: false,
                 rent_epoch: u64::MAX,
             },
-        );
+        )
+    }
+
+    /// Adds everything in the directory loaded by [`load_fixtures_dir`]:
+    /// - every `KeyedUiAccount` json file
+    /// - every `<program_id>.so` file as an upgradeable program with no upgrade authority
+    ///
+    /// ## Panics
+    /// With the path of the offending file if any file fails to load
+    fn add_fixtures_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        let fixtures = load_fixtures_dir(dir).unwrap_or_else(|e| panic!("{e}"));
+        for keyed_account in fixtures.accounts {
+            self = self.add_keyed_account(keyed_account);
+        }
+        for (program_id, so_prog_data) in fixtures.programs {
+            self = self.add_upgradeable_program_from_data(program_id, &so_prog_data, None, 0);
+        }
+        self
+    }
 
+    /// [`Self::add_fixtures_dir`] on `<test_fixtures_dir()>/relative_dir`
+    fn add_test_fixtures_dir<P: AsRef<Path>>(self, relative_dir: P) -> Self {
+        self.add_fixtures_dir(test_fixtures_dir().join(relative_dir))
+    }
+}
+
+impl ExtendedProgramTest for ProgramTest {
+    fn add_account_chained(mut self, address: Pubkey, account: Account) -> Self {
+        self.add_account(address, account);
         self
     }
 }
@@ -5,6 +5,8 @@ use solana_program_test::ProgramTestContext;
 use solana_sdk::{
     account::{Account, ReadableAccount},
     bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::{Clock, Epoch, UnixTimestamp},
+    hash::Hash,
     pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
@@ -36,22 +38,50 @@ pub trait ExtendedProgramTestContext {
     /// NB: this method calls [`ProgramTestContext::warp_forward_force_reward_interval_end`] several times,
     /// advancing the blocks of the test chain. This will affect tests that rely on sysvar::Clock being in a specific state,
     /// so be sure to make any modifications to sysvar::Clock only after calling this
-    async fn upgrade_program(
-        &mut self,
+    async fn upgrade_program<'a>(
+        &'a mut self,
         program_id: Pubkey,
         program_name: &str,
         signers: UpgradeProgramSigners<'async_trait>,
-    ) -> &mut Self;
+    ) -> &'a mut Self;
+
+    /// Warps to the first slot of `epoch`, then forward by 1 more slot to end
+    /// the epoch rewards interval, during which stake accounts cannot be modified.
+    ///
+    /// Named so to not be shadowed by the inherent [`ProgramTestContext::warp_to_epoch`],
+    /// which method resolution would always pick over a trait method of the same name.
+    /// Unlike it, this panics on error and returns `self` for chaining.
+    ///
+    /// NB: the bank recomputes sysvar::Clock on warping, so any previous
+    /// modifications to it are lost
+    ///
+    /// ## Panics
+    /// If `epoch` is not in the future
+    fn warp_to_epoch_chained(&mut self, epoch: Epoch) -> &mut Self;
+
+    /// Warps forward `n` epochs, one epoch boundary at a time,
+    /// so that stake history is recorded for every epoch
+    /// and delegated stake activates and deactivates as it would on a real cluster
+    async fn advance_epochs<'a>(&'a mut self, n: u64) -> &'a mut Self;
+
+    /// Sets sysvar::Clock to the current clock as modified by `f`
+    async fn set_clock<'a, F: FnOnce(&mut Clock) + Send>(&'a mut self, f: F) -> &'a mut Self;
+
+    async fn set_unix_timestamp<'a>(&'a mut self, unix_timestamp: UnixTimestamp) -> &'a mut Self;
+
+    /// Updates `last_blockhash` to a blockhash newer than the current one,
+    /// e.g. to send a transaction identical to an earlier one
+    async fn refresh_blockhash(&mut self) -> Hash;
 }
 
 #[async_trait]
 impl ExtendedProgramTestContext for ProgramTestContext {
-    async fn upgrade_program(
-        &mut self,
+    async fn upgrade_program<'a>(
+        &'a mut self,
         program_id: Pubkey,
         program_name: &str,
         signers: UpgradeProgramSigners<'async_trait>,
-    ) -> &mut Self {
+    ) -> &'a mut Self {
         let (prog_data_addr, _bump) =
             Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
 
@@ -133,6 +163,36 @@ impl ExtendedProgramTestContext for ProgramTestContext {
 
         self
     }
+
+    fn warp_to_epoch_chained(&mut self, epoch: Epoch) -> &mut Self {
+        self.warp_to_epoch(epoch).unwrap();
+        self.warp_forward_force_reward_interval_end().unwrap();
+        self
+    }
+
+    async fn advance_epochs<'a>(&'a mut self, n: u64) -> &'a mut Self {
+        let clock: Clock = self.banks_client.get_sysvar().await.unwrap();
+        for epoch in clock.epoch + 1..=clock.epoch + n {
+            self.warp_to_epoch_chained(epoch);
+        }
+        self
+    }
+
+    async fn set_clock<'a, F: FnOnce(&mut Clock) + Send>(&'a mut self, f: F) -> &'a mut Self {
+        let mut clock: Clock = self.banks_client.get_sysvar().await.unwrap();
+        f(&mut clock);
+        self.set_sysvar(&clock);
+        self
+    }
+
+    async fn set_unix_timestamp<'a>(&'a mut self, unix_timestamp: UnixTimestamp) -> &'a mut Self {
+        self.set_clock(|clock| clock.unix_timestamp = unix_timestamp)
+            .await
+    }
+
+    async fn refresh_blockhash(&mut self) -> Hash {
+        self.get_new_latest_blockhash().await.unwrap()
+    }
 }
 
 /*
@@ -160,3 +220,91 @@ mod tests {
     }
 }
 */
+
+#[cfg(test)]
+mod clock_tests {
+    use solana_program_test::ProgramTest;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn clock_helpers() {
+        let mut ctx = ProgramTest::default().start_with_context().await;
+
+        ctx.warp_to_epoch_chained(2).advance_epochs(3).await;
+        let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+        assert_eq!(clock.epoch, 5);
+
+        ctx.set_unix_timestamp(1_700_000_000).await;
+        let after: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+        assert_eq!(after.unix_timestamp, 1_700_000_000);
+        assert_eq!(after.epoch, clock.epoch);
+        assert_eq!(after.slot, clock.slot);
+
+        ctx.set_clock(|c| c.epoch_start_timestamp = 1).await;
+        let after: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+        assert_eq!(after.epoch_start_timestamp, 1);
+        assert_eq!(after.unix_timestamp, 1_700_000_000);
+
+        let old = ctx.last_blockhash;
+        assert_ne!(ctx.refresh_blockhash().await, old);
+        assert_ne!(ctx.last_blockhash, old);
+    }
+
+    #[cfg(all(feature = "stake", feature = "vote"))]
+    #[tokio::test]
+    async fn advance_epochs_activates_stake() {
+        use solana_program::{
+            native_token::LAMPORTS_PER_SOL,
+            stake::{instruction::delegate_stake, state::StakeStateV2},
+            stake_history::StakeHistory,
+        };
+
+        use crate::{
+            stake::{SingleAuthorityAuthorized, StakeAccountFixtureBuilder, StakeLifecycle},
+            vote::{mock_validators, VoteProgramTest},
+            ExtendedProgramTest, IntoAccount,
+        };
+
+        let validators = mock_validators(1, 0);
+        let staker = Keypair::new();
+        let stake_addr = Pubkey::new_unique();
+        let mut ctx = ProgramTest::default()
+            .add_mock_validators(&validators)
+            .add_account_chained(
+                stake_addr,
+                StakeAccountFixtureBuilder::new(StakeLifecycle::Initialized, 0)
+                    .authorized(SingleAuthorityAuthorized(staker.pubkey()))
+                    .staked_lamports(10 * LAMPORTS_PER_SOL)
+                    .into_account(),
+            )
+            .start_with_context()
+            .await;
+        ctx.warp_to_epoch_chained(1);
+
+        let mut tx = Transaction::new_with_payer(
+            &[delegate_stake(
+                &stake_addr,
+                &staker.pubkey(),
+                &validators[0].vote,
+            )],
+            Some(&ctx.payer.pubkey()),
+        );
+        tx.sign(&[&ctx.payer, &staker], ctx.last_blockhash);
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+
+        ctx.advance_epochs(2).await;
+        let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+        assert_eq!(clock.epoch, 3);
+        let stake_history: StakeHistory = ctx.banks_client.get_sysvar().await.unwrap();
+        let stake: StakeStateV2 =
+            bincode::deserialize(&ctx.banks_client.get_account_data(stake_addr).await).unwrap();
+        let delegation = stake.delegation().unwrap();
+        assert_eq!(delegation.activation_epoch, 1);
+        let status =
+            delegation.stake_activating_and_deactivating(clock.epoch, &stake_history, None);
+        assert_eq!(status.effective, delegation.stake);
+        assert_eq!(status.activating, 0);
+        assert_eq!(status.deactivating, 0);
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use solana_program::pubkey::{ParsePubkeyError, Pubkey};
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::account::Account;
+
+use crate::{KeyedUiAccount, KeyedUiAccountError};
+
+#[derive(Debug)]
+pub enum FixtureLoadErrorKind {
+    Io(std::io::Error),
+
+    Json(serde_json::Error),
+
+    /// .so file name that is not a program ID
+    Pubkey(ParsePubkeyError),
+
+    Account(KeyedUiAccountError),
+}
+
+impl Display for FixtureLoadErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Json(e) => write!(f, "Invalid KeyedUiAccount json: {e}"),
+            Self::Pubkey(e) => write!(f, "Invalid program ID: {e}"),
+            Self::Account(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// A fixture file that failed to load
+#[derive(Debug)]
+pub struct FixtureLoadError {
+    pub path: PathBuf,
+    pub kind: FixtureLoadErrorKind,
+}
+
+impl FixtureLoadError {
+    pub fn new<P: AsRef<Path>>(path: P, kind: FixtureLoadErrorKind) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            kind,
+        }
+    }
+}
+
+impl Display for FixtureLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Failed to load fixture {}: {}",
+            self.path.display(),
+            self.kind
+        )
+    }
+}
+
+impl Error for FixtureLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            FixtureLoadErrorKind::Io(e) => Some(e),
+            FixtureLoadErrorKind::Json(e) => Some(e),
+            FixtureLoadErrorKind::Pubkey(e) => Some(e),
+            FixtureLoadErrorKind::Account(e) => Some(e),
+        }
+    }
+}
+
+/// Contents of a fixtures directory
+#[derive(Clone, Debug, Default)]
+pub struct FixturesDir {
+    pub accounts: Vec<Keyed<Account>>,
+
+    /// `(program_id, so_prog_data)`
+    pub programs: Vec<(Pubkey, Vec<u8>)>,
+}
+
+/// Recursively loads, in file name order:
+/// - every `*.json` file as a [`KeyedUiAccount`]
+/// - every `<program_id>.so` file as a compiled program
+///
+/// Other files, and `*.json` files that are not objects with a `pubkey` or `account` key,
+/// are ignored
+pub fn load_fixtures_dir<P: AsRef<Path>>(dir: P) -> Result<FixturesDir, FixtureLoadError> {
+    let mut res = FixturesDir::default();
+    load_fixtures_dir_into(dir.as_ref(), &mut res)?;
+    Ok(res)
+}
+
+fn load_fixtures_dir_into(dir: &Path, res: &mut FixturesDir) -> Result<(), FixtureLoadError> {
+    let io_err = |path: &Path| {
+        let path = path.to_path_buf();
+        move |e: std::io::Error| FixtureLoadError::new(path, FixtureLoadErrorKind::Io(e))
+    };
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(io_err(dir))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io_err(dir))?;
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            load_fixtures_dir_into(&path, res)?;
+            continue;
+        }
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => res.accounts.extend(load_keyed_account(&path)?),
+            Some("so") => {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                let program_id = Pubkey::from_str(&stem)
+                    .map_err(|e| FixtureLoadError::new(&path, FixtureLoadErrorKind::Pubkey(e)))?;
+                let so_prog_data = std::fs::read(&path).map_err(io_err(&path))?;
+                res.programs.push((program_id, so_prog_data));
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Returns `None` if the json is not account-like, e.g. a config file of some other tool.
+/// Account-like but malformed json still errors
+fn load_keyed_account(path: &Path) -> Result<Option<Keyed<Account>>, FixtureLoadError> {
+    let json_err =
+        |e: serde_json::Error| FixtureLoadError::new(path, FixtureLoadErrorKind::Json(e));
+    let bytes = std::fs::read(path)
+        .map_err(|e| FixtureLoadError::new(path, FixtureLoadErrorKind::Io(e)))?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).map_err(json_err)?;
+    let is_account_like = value
+        .as_object()
+        .is_some_and(|obj| obj.contains_key("pubkey") || obj.contains_key("account"));
+    if !is_account_like {
+        return Ok(None);
+    }
+    let keyed_ui_account: KeyedUiAccount = serde_json::from_value(value).map_err(json_err)?;
+    keyed_ui_account
+        .try_to_keyed_account()
+        .map(Some)
+        .map_err(|e| FixtureLoadError::new(path, FixtureLoadErrorKind::Account(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_account_decoder::{UiAccount, UiAccountEncoding};
+    use solana_program::system_program;
+
+    use super::*;
+
+    fn write_account_fixture(path: &Path, pubkey: Pubkey, account: &Account) {
+        let keyed_ui_account = KeyedUiAccount {
+            pubkey: pubkey.to_string(),
+            account: UiAccount::encode(&pubkey, account, UiAccountEncoding::Base64, None, None),
+        };
+        std::fs::write(path, serde_json::to_vec(&keyed_ui_account).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn load_nested_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let account = Account::new(1_000, 3, &system_program::ID);
+        write_account_fixture(&dir.path().join("a.json"), a, &account);
+        write_account_fixture(&nested.join("b.json"), b, &account);
+        let program_id = Pubkey::new_unique();
+        std::fs::write(nested.join(format!("{program_id}.so")), [1, 2, 3]).unwrap();
+        std::fs::write(dir.path().join("README.md"), "ignored").unwrap();
+
+        let FixturesDir { accounts, programs } = load_fixtures_dir(dir.path()).unwrap();
+        assert_eq!(
+            accounts.iter().map(|k| k.pubkey).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(accounts[1].account, account);
+        assert_eq!(programs, vec![(program_id, vec![1, 2, 3])]);
+    }
+
+    #[test]
+    fn skips_non_account_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = Pubkey::new_unique();
+        let account = Account::new(1_000, 3, &system_program::ID);
+        write_account_fixture(&dir.path().join("account.json"), key, &account);
+        std::fs::write(dir.path().join("config.json"), r#"{"cluster": "localnet"}"#).unwrap();
+        std::fs::write(dir.path().join("list.json"), "[1, 2, 3]").unwrap();
+
+        let FixturesDir { accounts, programs } = load_fixtures_dir(dir.path()).unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].pubkey, key);
+        assert!(programs.is_empty());
+    }
+
+    #[test]
+    fn errors_name_offending_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let bad = dir.path().join("bad.json");
+        // account-like but missing `account`
+        std::fs::write(&bad, format!(r#"{{"pubkey": "{}"}}"#, Pubkey::new_unique())).unwrap();
+        let err = load_fixtures_dir(dir.path()).unwrap_err();
+        assert_eq!(err.path, bad);
+        assert!(matches!(err.kind, FixtureLoadErrorKind::Json(_)));
+        assert!(err.to_string().contains("bad.json"));
+
+        std::fs::remove_file(&bad).unwrap();
+        let bad = dir.path().join("not-a-pubkey.so");
+        std::fs::write(&bad, [0]).unwrap();
+        let err = load_fixtures_dir(dir.path()).unwrap_err();
+        assert_eq!(err.path, bad);
+        assert!(matches!(err.kind, FixtureLoadErrorKind::Pubkey(_)));
+    }
+}
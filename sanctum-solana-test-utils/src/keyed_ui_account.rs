@@ -1,12 +1,96 @@
-use std::{fs::File, path::Path, str::FromStr};
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::File,
+    io::{BufReader, Write},
+    num::ParseIntError,
+    path::Path,
+    str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
-use solana_account_decoder::UiAccount;
-use solana_program::pubkey::Pubkey;
+use solana_account_decoder::{
+    parse_account_data::ParsedAccount,
+    parse_stake::{StakeAccountType, UiStakeAccount},
+    parse_token::{TokenAccountType, UiAccountState, UiMint, UiTokenAccount},
+    UiAccount, UiAccountData, UiAccountEncoding,
+};
+use solana_program::{
+    program_option::COption,
+    pubkey::{ParsePubkeyError, Pubkey},
+    stake::{
+        stake_flags::StakeFlags,
+        state::{Authorized, Delegation, Lockup, Meta, Stake, StakeStateV2},
+    },
+};
 use solana_readonly_account::keyed::Keyed;
 use solana_sdk::account::Account;
 
-use crate::test_fixtures_dir;
+use crate::{
+    test_fixtures_dir, FixtureLoadError, FixtureLoadErrorKind, TOKEN_ACCOUNT_LEN, TOKEN_MINT_LEN,
+};
+
+#[derive(Debug)]
+pub enum KeyedUiAccountError {
+    /// Invalid pubkey, owner, or pubkey field of a jsonParsed account
+    Pubkey(ParsePubkeyError),
+
+    /// Binary data invalid for its encoding
+    UndecodableData,
+
+    /// jsonParsed account of a program or type that cannot be converted back to binary
+    UnsupportedParsedAccount {
+        program: String,
+        reason: String,
+    },
+
+    InvalidParsedAccount(serde_json::Error),
+
+    InvalidAmount(ParseIntError),
+}
+
+impl Display for KeyedUiAccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pubkey(e) => write!(f, "Invalid pubkey: {e}"),
+            Self::UndecodableData => f.write_str("Account data invalid for its encoding"),
+            Self::UnsupportedParsedAccount { program, reason } => {
+                write!(f, "Unsupported jsonParsed {program} account: {reason}")
+            }
+            Self::InvalidParsedAccount(e) => write!(f, "Invalid jsonParsed account: {e}"),
+            Self::InvalidAmount(e) => write!(f, "Invalid amount: {e}"),
+        }
+    }
+}
+
+impl Error for KeyedUiAccountError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Pubkey(e) => Some(e),
+            Self::InvalidParsedAccount(e) => Some(e),
+            Self::InvalidAmount(e) => Some(e),
+            Self::UndecodableData | Self::UnsupportedParsedAccount { .. } => None,
+        }
+    }
+}
+
+impl From<ParsePubkeyError> for KeyedUiAccountError {
+    fn from(e: ParsePubkeyError) -> Self {
+        Self::Pubkey(e)
+    }
+}
+
+impl From<serde_json::Error> for KeyedUiAccountError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::InvalidParsedAccount(e)
+    }
+}
+
+impl From<ParseIntError> for KeyedUiAccountError {
+    fn from(e: ParseIntError) -> Self {
+        Self::InvalidAmount(e)
+    }
+}
 
 /// This is the json format of
 /// `solana account -o <FILENAME>.json --output json <ACCOUNT-PUBKEY>`
@@ -18,8 +102,15 @@ pub struct KeyedUiAccount {
 
 impl KeyedUiAccount {
     pub fn from_file<P: AsRef<Path>>(json_file_path: P) -> Self {
-        let mut file = File::open(json_file_path).unwrap();
-        serde_json::from_reader(&mut file).unwrap()
+        Self::try_from_file(json_file_path).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_from_file<P: AsRef<Path>>(json_file_path: P) -> Result<Self, FixtureLoadError> {
+        let path = json_file_path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| FixtureLoadError::new(path, FixtureLoadErrorKind::Io(e)))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| FixtureLoadError::new(path, FixtureLoadErrorKind::Json(e)))
     }
 
     /// Loads a KeyedUiAccount from `<test_fixtures_dir()>/relative_json_file_path`
@@ -27,10 +118,448 @@ impl KeyedUiAccount {
         Self::from_file(test_fixtures_dir().join(relative_json_file_path))
     }
 
+    /// Encodes the account the same way `solana account --output json` does.
+    ///
+    /// With [`UiAccountEncoding::JsonParsed`], token accounts are encoded as base64
+    /// since the decimals of their mint are not known
+    pub fn from_keyed_account(
+        Keyed { pubkey, account }: &Keyed<Account>,
+        encoding: UiAccountEncoding,
+    ) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            account: UiAccount::encode(pubkey, account, encoding, None, None),
+        }
+    }
+
     pub fn to_keyed_account(&self) -> Keyed<Account> {
-        Keyed {
-            pubkey: Pubkey::from_str(&self.pubkey).unwrap(),
-            account: self.account.decode().unwrap(),
+        self.try_to_keyed_account()
+            .unwrap_or_else(|e| panic!("Account {}: {e}", self.pubkey))
+    }
+
+    /// Supports base58, base64, base64+zstd,
+    /// and jsonParsed token, token-2022 (without extensions) and stake accounts
+    pub fn try_to_keyed_account(&self) -> Result<Keyed<Account>, KeyedUiAccountError> {
+        let UiAccount {
+            lamports,
+            data,
+            owner,
+            executable,
+            rent_epoch,
+            ..
+        } = &self.account;
+        let data = match data {
+            UiAccountData::Json(parsed) => parsed_account_data(parsed)?,
+            binary => binary
+                .decode()
+                .ok_or(KeyedUiAccountError::UndecodableData)?,
+        };
+        Ok(Keyed {
+            pubkey: Pubkey::from_str(&self.pubkey)?,
+            account: Account {
+                lamports: *lamports,
+                data,
+                owner: Pubkey::from_str(owner)?,
+                executable: *executable,
+                rent_epoch: *rent_epoch,
+            },
+        })
+    }
+
+    /// Writes the account as pretty-printed json, creating parent directories if required
+    pub fn try_to_file<P: AsRef<Path>>(&self, json_file_path: P) -> std::io::Result<()> {
+        let path = json_file_path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = File::create(path)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.write_all(b"\n")
+    }
+
+    pub fn to_file<P: AsRef<Path>>(&self, json_file_path: P) {
+        let path = json_file_path.as_ref();
+        self.try_to_file(path)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Writes the account to `<test_fixtures_dir()>/relative_json_file_path`
+    pub fn to_test_fixtures_file<P: AsRef<Path>>(&self, relative_json_file_path: P) {
+        self.to_file(test_fixtures_dir().join(relative_json_file_path))
+    }
+}
+
+fn parsed_account_data(
+    ParsedAccount {
+        program,
+        parsed,
+        space,
+    }: &ParsedAccount,
+) -> Result<Vec<u8>, KeyedUiAccountError> {
+    let unsupported = |reason: &str| KeyedUiAccountError::UnsupportedParsedAccount {
+        program: program.clone(),
+        reason: reason.to_owned(),
+    };
+    let check_space = |expected: usize| {
+        if *space == expected as u64 {
+            Ok(())
+        } else {
+            Err(unsupported("space does not match account type"))
+        }
+    };
+    match program.as_str() {
+        "spl-token" | "spl-token-2022" => {
+            match serde_json::from_value::<TokenAccountType>(parsed.clone())? {
+                TokenAccountType::Account(account) => {
+                    if !account.extensions.is_empty() {
+                        return Err(unsupported("extensions"));
+                    }
+                    check_space(TOKEN_ACCOUNT_LEN)?;
+                    pack_token_account(&account)
+                }
+                TokenAccountType::Mint(mint) => {
+                    if !mint.extensions.is_empty() {
+                        return Err(unsupported("extensions"));
+                    }
+                    check_space(TOKEN_MINT_LEN)?;
+                    pack_mint(&mint)
+                }
+                TokenAccountType::Multisig(_) => Err(unsupported("multisig")),
+            }
+        }
+        "stake" => {
+            let stake_state = match serde_json::from_value::<StakeAccountType>(parsed.clone())? {
+                StakeAccountType::Uninitialized => StakeStateV2::Uninitialized,
+                StakeAccountType::Initialized(UiStakeAccount { meta, .. }) => {
+                    StakeStateV2::Initialized(stake_meta(&meta)?)
+                }
+                StakeAccountType::Delegated(UiStakeAccount { meta, stake }) => {
+                    let stake = stake.ok_or_else(|| unsupported("delegated without stake"))?;
+                    let delegation = stake.delegation;
+                    StakeStateV2::Stake(
+                        stake_meta(&meta)?,
+                        Stake {
+                            delegation: Delegation {
+                                voter_pubkey: Pubkey::from_str(&delegation.voter)?,
+                                stake: delegation.stake.parse()?,
+                                activation_epoch: delegation.activation_epoch.parse()?,
+                                deactivation_epoch: delegation.deactivation_epoch.parse()?,
+                                ..Default::default()
+                            },
+                            credits_observed: stake.credits_observed,
+                        },
+                        StakeFlags::empty(),
+                    )
+                }
+                StakeAccountType::RewardsPool => StakeStateV2::RewardsPool,
+            };
+            check_space(StakeStateV2::size_of())?;
+            let mut data = vec![0u8; StakeStateV2::size_of()];
+            bincode::serialize_into(data.as_mut_slice(), &stake_state)
+                .map_err(|_| unsupported("stake account serialization failed"))?;
+            Ok(data)
+        }
+        _ => Err(unsupported("unknown program")),
+    }
+}
+
+fn stake_meta(
+    meta: &solana_account_decoder::parse_stake::UiMeta,
+) -> Result<Meta, KeyedUiAccountError> {
+    Ok(Meta {
+        rent_exempt_reserve: meta.rent_exempt_reserve.parse()?,
+        authorized: Authorized {
+            staker: Pubkey::from_str(&meta.authorized.staker)?,
+            withdrawer: Pubkey::from_str(&meta.authorized.withdrawer)?,
+        },
+        lockup: Lockup {
+            unix_timestamp: meta.lockup.unix_timestamp,
+            epoch: meta.lockup.epoch,
+            custodian: Pubkey::from_str(&meta.lockup.custodian)?,
+        },
+    })
+}
+
+fn parse_opt_pubkey(pk: &Option<String>) -> Result<COption<Pubkey>, KeyedUiAccountError> {
+    Ok(match pk {
+        Some(pk) => COption::Some(Pubkey::from_str(pk)?),
+        None => COption::None,
+    })
+}
+
+fn write_coption_pubkey(out: &mut Vec<u8>, pk: COption<Pubkey>) {
+    match pk {
+        COption::Some(pk) => {
+            out.extend(1u32.to_le_bytes());
+            out.extend(pk.to_bytes());
+        }
+        COption::None => out.extend([0u8; 36]),
+    }
+}
+
+/// Same layout as `spl_token::state::Account::pack()`
+fn pack_token_account(account: &UiTokenAccount) -> Result<Vec<u8>, KeyedUiAccountError> {
+    let mut res = Vec::with_capacity(TOKEN_ACCOUNT_LEN);
+    res.extend(Pubkey::from_str(&account.mint)?.to_bytes());
+    res.extend(Pubkey::from_str(&account.owner)?.to_bytes());
+    res.extend(account.token_amount.amount.parse::<u64>()?.to_le_bytes());
+    write_coption_pubkey(&mut res, parse_opt_pubkey(&account.delegate)?);
+    res.push(match account.state {
+        UiAccountState::Uninitialized => 0,
+        UiAccountState::Initialized => 1,
+        UiAccountState::Frozen => 2,
+    });
+    match &account.rent_exempt_reserve {
+        Some(reserve) if account.is_native => {
+            res.extend(1u32.to_le_bytes());
+            res.extend(reserve.amount.parse::<u64>()?.to_le_bytes());
         }
+        _ => res.extend([0u8; 12]),
+    }
+    let delegated_amount = match &account.delegated_amount {
+        Some(amt) => amt.amount.parse()?,
+        None => 0u64,
+    };
+    res.extend(delegated_amount.to_le_bytes());
+    write_coption_pubkey(&mut res, parse_opt_pubkey(&account.close_authority)?);
+    Ok(res)
+}
+
+/// Same layout as `spl_token::state::Mint::pack()`
+fn pack_mint(mint: &UiMint) -> Result<Vec<u8>, KeyedUiAccountError> {
+    let mut res = Vec::with_capacity(TOKEN_MINT_LEN);
+    write_coption_pubkey(&mut res, parse_opt_pubkey(&mint.mint_authority)?);
+    res.extend(mint.supply.parse::<u64>()?.to_le_bytes());
+    res.push(mint.decimals);
+    res.push(mint.is_initialized.into());
+    write_coption_pubkey(&mut res, parse_opt_pubkey(&mint.freeze_authority)?);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{stake, system_program};
+
+    use super::*;
+
+    fn roundtrip(keyed: &Keyed<Account>, encoding: UiAccountEncoding) -> Keyed<Account> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("account.json");
+        KeyedUiAccount::from_keyed_account(keyed, encoding).to_file(&path);
+        KeyedUiAccount::try_from_file(&path)
+            .unwrap()
+            .try_to_keyed_account()
+            .unwrap()
+    }
+
+    #[test]
+    fn binary_encodings_roundtrip() {
+        let keyed = Keyed {
+            pubkey: Pubkey::new_unique(),
+            account: Account {
+                lamports: 1_000_000,
+                data: (0..100).collect(),
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        };
+        for encoding in [
+            UiAccountEncoding::Base58,
+            UiAccountEncoding::Base64,
+            UiAccountEncoding::Base64Zstd,
+            UiAccountEncoding::JsonParsed,
+        ] {
+            assert_eq!(roundtrip(&keyed, encoding), keyed, "{encoding:?}");
+        }
+    }
+
+    #[test]
+    fn json_parsed_stake_roundtrip() {
+        let stake_state = StakeStateV2::Stake(
+            Meta {
+                rent_exempt_reserve: 2_282_880,
+                authorized: Authorized::auto(&Pubkey::new_unique()),
+                lockup: Lockup {
+                    unix_timestamp: 1,
+                    epoch: 2,
+                    custodian: Pubkey::new_unique(),
+                },
+            },
+            Stake {
+                delegation: Delegation::new(&Pubkey::new_unique(), 1_000_000_000, 5),
+                credits_observed: 69,
+            },
+            StakeFlags::empty(),
+        );
+        let mut data = vec![0u8; StakeStateV2::size_of()];
+        bincode::serialize_into(data.as_mut_slice(), &stake_state).unwrap();
+        let keyed = Keyed {
+            pubkey: Pubkey::new_unique(),
+            account: Account {
+                lamports: 1_002_282_880,
+                data,
+                owner: stake::program::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        };
+        let ui = KeyedUiAccount::from_keyed_account(&keyed, UiAccountEncoding::JsonParsed);
+        assert!(matches!(ui.account.data, UiAccountData::Json(_)));
+        assert_eq!(ui.try_to_keyed_account().unwrap(), keyed);
+    }
+
+    #[cfg(feature = "token")]
+    #[test]
+    fn json_parsed_token_account() {
+        use crate::{token::tokenkeg::mock_tokenkeg_account, IntoAccount};
+
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let expected = mock_tokenkeg_account(crate::token::MockTokenAccountArgs {
+            mint,
+            authority,
+            amount: 420,
+        })
+        .into_account();
+        let ui: KeyedUiAccount = serde_json::from_value(serde_json::json!({
+            "pubkey": Pubkey::new_unique().to_string(),
+            "account": {
+                "lamports": expected.lamports,
+                "data": {
+                    "program": "spl-token",
+                    "parsed": {
+                        "type": "account",
+                        "info": {
+                            "mint": mint.to_string(),
+                            "owner": authority.to_string(),
+                            "tokenAmount": {
+                                "uiAmount": 0.42,
+                                "decimals": 3,
+                                "amount": "420",
+                                "uiAmountString": "0.42"
+                            },
+                            "state": "initialized",
+                            "isNative": false
+                        }
+                    },
+                    "space": 165
+                },
+                "owner": spl_token::ID.to_string(),
+                "executable": false,
+                "rentEpoch": u64::MAX,
+                "space": 165
+            }
+        }))
+        .unwrap();
+        assert_eq!(ui.to_keyed_account().account, expected);
+    }
+
+    #[cfg(feature = "token")]
+    #[test]
+    fn pack_matches_spl_token() {
+        use solana_account_decoder::parse_account_data::{
+            AccountAdditionalDataV2, SplTokenAdditionalData,
+        };
+        use solana_program::program_pack::Pack;
+        use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+        let token_account = TokenAccount {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 3_000_000_000,
+            delegate: COption::Some(Pubkey::new_unique()),
+            state: AccountState::Frozen,
+            is_native: COption::Some(2_039_280),
+            delegated_amount: 420,
+            close_authority: COption::Some(Pubkey::new_unique()),
+        };
+        let mint = Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            supply: 69_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut token_account_data = vec![0u8; TokenAccount::LEN];
+        token_account.pack_into_slice(&mut token_account_data);
+        let mut mint_data = vec![0u8; Mint::LEN];
+        mint.pack_into_slice(&mut mint_data);
+
+        for data in [token_account_data, mint_data] {
+            let pubkey = Pubkey::new_unique();
+            let account = Account {
+                lamports: 1_000_000_000,
+                data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            };
+            let ui = KeyedUiAccount {
+                pubkey: pubkey.to_string(),
+                account: UiAccount::encode(
+                    &pubkey,
+                    &account,
+                    UiAccountEncoding::JsonParsed,
+                    Some(AccountAdditionalDataV2 {
+                        spl_token_additional_data: Some(SplTokenAdditionalData {
+                            decimals: 6,
+                            interest_bearing_config: None,
+                        }),
+                    }),
+                    None,
+                ),
+            };
+            assert!(matches!(ui.account.data, UiAccountData::Json(_)));
+            assert_eq!(ui.to_keyed_account().account.data, account.data);
+        }
+    }
+
+    #[test]
+    fn parsed_account_space_mismatch_errors() {
+        let ui: KeyedUiAccount = serde_json::from_value(serde_json::json!({
+            "pubkey": Pubkey::new_unique().to_string(),
+            "account": {
+                "lamports": 1,
+                "data": {
+                    "program": "stake",
+                    "parsed": {
+                        "type": "uninitialized"
+                    },
+                    "space": u64::MAX
+                },
+                "owner": stake::program::ID.to_string(),
+                "executable": false,
+                "rentEpoch": 0
+            }
+        }))
+        .unwrap();
+        assert!(matches!(
+            ui.try_to_keyed_account(),
+            Err(KeyedUiAccountError::UnsupportedParsedAccount { .. })
+        ));
+    }
+
+    #[test]
+    fn unsupported_parsed_account_errors() {
+        let ui: KeyedUiAccount = serde_json::from_value(serde_json::json!({
+            "pubkey": Pubkey::new_unique().to_string(),
+            "account": {
+                "lamports": 1,
+                "data": {
+                    "program": "vote",
+                    "parsed": {},
+                    "space": 3762
+                },
+                "owner": system_program::ID.to_string(),
+                "executable": false,
+                "rentEpoch": 0
+            }
+        }))
+        .unwrap();
+        assert!(matches!(
+            ui.try_to_keyed_account(),
+            Err(KeyedUiAccountError::UnsupportedParsedAccount { .. })
+        ));
     }
 }
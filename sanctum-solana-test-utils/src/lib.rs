@@ -1,20 +1,34 @@
+mod account_diff;
 mod consts;
+mod cu_baseline;
+mod differential;
 mod extended_banks_client;
 mod extended_program_test;
 mod extended_program_test_context;
+mod fixtures_dir;
 mod into_account;
 mod keyed_ui_account;
+mod logs;
 mod paths;
+mod try_extended_banks_client;
 mod tx;
+mod wallet;
 
+pub use account_diff::*;
 pub use consts::*;
+pub use cu_baseline::*;
+pub use differential::*;
 pub use extended_banks_client::*;
 pub use extended_program_test::*;
 pub use extended_program_test_context::*;
+pub use fixtures_dir::*;
 pub use into_account::*;
 pub use keyed_ui_account::*;
+pub use logs::*;
 pub use paths::*;
+pub use try_extended_banks_client::*;
 pub use tx::*;
+pub use wallet::*;
 
 // re-export KeyedAccount
 pub use solana_readonly_account::keyed::Keyed;
@@ -23,6 +37,10 @@ pub use solana_readonly_account::keyed::Keyed;
 #[cfg_attr(docsrs, doc(cfg(feature = "banks-rpc-server")))]
 pub mod banks_rpc_server;
 
+#[cfg(feature = "capture")]
+#[cfg_attr(docsrs, doc(cfg(feature = "capture")))]
+pub mod capture;
+
 #[cfg(feature = "cli")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cli")))]
 pub mod cli;
@@ -31,6 +49,10 @@ pub mod cli;
 #[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
 pub mod proptest_utils;
 
+#[cfg(feature = "spl-stake-pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "spl-stake-pool")))]
+pub mod spl_stake_pool;
+
 #[cfg(feature = "stake")]
 #[cfg_attr(docsrs, doc(cfg(feature = "stake")))]
 pub mod stake;
@@ -38,3 +60,7 @@ pub mod stake;
 #[cfg(any(feature = "token", feature = "token-2022"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "token", feature = "token-2022"))))]
 pub mod token;
+
+#[cfg(feature = "vote")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vote")))]
+pub mod vote;
@@ -0,0 +1,364 @@
+use std::{fmt::Display, str::FromStr};
+
+use data_encoding::BASE64;
+use solana_program::pubkey::Pubkey;
+
+use crate::ExecOutput;
+
+/// A single program invocation parsed from a transaction's log messages
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramInvocation {
+    pub program_id: Pubkey,
+
+    /// 1 for instructions of the transaction, 2 for their CPIs etc
+    pub depth: usize,
+
+    /// `msg!()` lines, without the `Program log: ` prefix
+    pub logs: Vec<String>,
+
+    /// `sol_log_data()` lines, each a list of the logged byte slices
+    pub data: Vec<Vec<Vec<u8>>>,
+
+    pub return_data: Option<Vec<u8>>,
+
+    /// `None` for builtin programs, which do not log consumed compute units
+    pub compute_units_consumed: Option<u64>,
+
+    /// `None` if the logs were truncated before the invocation completed.
+    /// The error is the string logged by the runtime, e.g. `custom program error: 0x1`
+    pub result: Option<Result<(), String>>,
+
+    /// Lines logged by this invocation that are none of the above
+    pub other: Vec<String>,
+
+    /// CPIs made by this invocation, in order
+    pub inner: Vec<ProgramInvocation>,
+}
+
+impl ProgramInvocation {
+    fn new(program_id: Pubkey, depth: usize) -> Self {
+        Self {
+            program_id,
+            depth,
+            ..Default::default()
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        matches!(self.result, Some(Ok(())))
+    }
+
+    /// This invocation followed by all its CPIs, depth-first
+    pub fn iter(&self) -> impl Iterator<Item = &ProgramInvocation> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            stack.extend(next.inner.iter().rev());
+            Some(next)
+        })
+    }
+
+    /// Panics if no `msg!()` line of this invocation contains `needle`
+    pub fn assert_logged(&self, needle: &str) {
+        assert!(
+            self.logs.iter().any(|l| l.contains(needle)),
+            "{} did not log {needle:?}. Logs:\n{self}",
+            self.program_id
+        );
+    }
+
+    /// Panics if this invocation consumed `max` compute units or more,
+    /// or if it did not log its consumed compute units
+    pub fn assert_cu_below(&self, max: u64) {
+        let cu = self.compute_units_consumed.unwrap_or_else(|| {
+            panic!(
+                "{} did not log consumed compute units. Logs:\n{self}",
+                self.program_id
+            )
+        });
+        assert!(
+            cu < max,
+            "{} consumed {cu} CUs, expected < {max}. Logs:\n{self}",
+            self.program_id
+        );
+    }
+
+    /// Panics if neither this invocation nor any of its CPIs is of `program_id`
+    pub fn assert_invoked_program(&self, program_id: &Pubkey) {
+        assert!(
+            self.iter().any(|i| i.program_id == *program_id),
+            "{program_id} not invoked. Logs:\n{self}"
+        );
+    }
+
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = "  ".repeat(self.depth.saturating_sub(1));
+        writeln!(f, "{indent}{} [{}]", self.program_id, self.depth)?;
+        for l in self.logs.iter() {
+            writeln!(f, "{indent}  log: {l}")?;
+        }
+        for d in self.data.iter() {
+            let fields: Vec<String> = d.iter().map(|field| BASE64.encode(field)).collect();
+            writeln!(f, "{indent}  data: {}", fields.join(" "))?;
+        }
+        for l in self.other.iter() {
+            writeln!(f, "{indent}  {l}")?;
+        }
+        for i in self.inner.iter() {
+            i.fmt_indented(f)?;
+        }
+        if let Some(cu) = self.compute_units_consumed {
+            writeln!(f, "{indent}  consumed {cu} CUs")?;
+        }
+        match &self.result {
+            Some(Ok(())) => writeln!(f, "{indent}  success"),
+            Some(Err(e)) => writeln!(f, "{indent}  failed: {e}"),
+            None => writeln!(f, "{indent}  incomplete"),
+        }
+    }
+}
+
+impl Display for ProgramInvocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f)
+    }
+}
+
+/// A transaction's log messages parsed into a tree of program invocations
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParsedLogs {
+    /// One per instruction of the transaction that was executed
+    pub instructions: Vec<ProgramInvocation>,
+
+    /// Lines outside of any invocation
+    pub other: Vec<String>,
+
+    /// Whether the runtime truncated the logs for exceeding the log limit
+    pub truncated: bool,
+}
+
+impl ParsedLogs {
+    /// Never fails: unrecognized lines go into `other` of the invocation they are in
+    pub fn parse<S: AsRef<str>>(log_messages: &[S]) -> Self {
+        let mut res = Self::default();
+        let mut stack: Vec<ProgramInvocation> = Vec::new();
+        for line in log_messages.iter().map(AsRef::as_ref) {
+            if line == "Log truncated" {
+                res.truncated = true;
+                continue;
+            }
+            if let Some((program_id, depth)) = parse_invoke(line) {
+                stack.push(ProgramInvocation::new(program_id, depth));
+                continue;
+            }
+            let curr = match stack.last_mut() {
+                Some(c) => c,
+                None => {
+                    res.other.push(line.to_owned());
+                    continue;
+                }
+            };
+            if let Some(msg) = line.strip_prefix("Program log: ") {
+                curr.logs.push(msg.to_owned());
+            } else if let Some(data) = line.strip_prefix("Program data: ") {
+                curr.data.push(
+                    data.split(' ')
+                        .filter_map(|field| BASE64.decode(field.as_bytes()).ok())
+                        .collect(),
+                );
+            } else if let Some(ret) = line.strip_prefix("Program return: ") {
+                curr.return_data = ret
+                    .split_once(' ')
+                    .and_then(|(_program_id, b64)| BASE64.decode(b64.as_bytes()).ok());
+            } else if let Some(cu) = parse_consumed(line, &curr.program_id) {
+                curr.compute_units_consumed = Some(cu);
+            } else if let Some(result) = parse_result(line, &curr.program_id) {
+                curr.result = Some(result);
+                let done = stack.pop().unwrap();
+                res.push_completed(&mut stack, done);
+            } else {
+                curr.other.push(line.to_owned());
+            }
+        }
+        // unwind invocations cut off by truncation
+        while let Some(incomplete) = stack.pop() {
+            res.push_completed(&mut stack, incomplete);
+        }
+        res
+    }
+
+    fn push_completed(&mut self, stack: &mut [ProgramInvocation], invocation: ProgramInvocation) {
+        match stack.last_mut() {
+            Some(parent) => parent.inner.push(invocation),
+            None => self.instructions.push(invocation),
+        }
+    }
+
+    /// The invocation of the `index`th instruction of the transaction
+    pub fn instruction(&self, index: usize) -> &ProgramInvocation {
+        self.instructions.get(index).unwrap_or_else(|| {
+            panic!(
+                "Only {} instructions executed. Logs:\n{self}",
+                self.instructions.len()
+            )
+        })
+    }
+
+    /// All invocations depth-first, in the order they were made
+    pub fn iter(&self) -> impl Iterator<Item = &ProgramInvocation> {
+        self.instructions.iter().flat_map(|i| i.iter())
+    }
+
+    /// Panics if no `msg!()` line of any invocation contains `needle`
+    pub fn assert_logged(&self, needle: &str) {
+        assert!(
+            self.iter()
+                .any(|i| i.logs.iter().any(|l| l.contains(needle))),
+            "{needle:?} not logged. Logs:\n{self}"
+        );
+    }
+
+    /// Panics if any instruction of the transaction consumed `max` compute units or more
+    pub fn assert_cu_below(&self, max: u64) {
+        self.instructions
+            .iter()
+            .filter(|i| i.compute_units_consumed.is_some())
+            .for_each(|i| i.assert_cu_below(max));
+    }
+
+    /// Panics if `program_id` was not invoked at any depth
+    pub fn assert_invoked_program(&self, program_id: &Pubkey) {
+        assert!(
+            self.iter().any(|i| i.program_id == *program_id),
+            "{program_id} not invoked. Logs:\n{self}"
+        );
+    }
+}
+
+impl Display for ParsedLogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for l in self.other.iter() {
+            writeln!(f, "{l}")?;
+        }
+        for i in self.instructions.iter() {
+            i.fmt_indented(f)?;
+        }
+        if self.truncated {
+            writeln!(f, "Log truncated")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> ExecOutput<T> {
+    pub fn parse_logs(&self) -> ParsedLogs {
+        ParsedLogs::parse(&self.log_messages)
+    }
+}
+
+/// `Program {program_id} invoke [{depth}]`
+fn parse_invoke(line: &str) -> Option<(Pubkey, usize)> {
+    let rest = line.strip_prefix("Program ")?;
+    let (program_id, rest) = rest.split_once(' ')?;
+    let depth = rest.strip_prefix("invoke [")?.strip_suffix(']')?;
+    Some((Pubkey::from_str(program_id).ok()?, depth.parse().ok()?))
+}
+
+/// `Program {program_id} consumed {consumed} of {remaining} compute units`
+fn parse_consumed(line: &str, program_id: &Pubkey) -> Option<u64> {
+    let rest = line
+        .strip_prefix("Program ")?
+        .strip_prefix(program_id.to_string().as_str())?
+        .strip_prefix(" consumed ")?;
+    let (consumed, _) = rest.split_once(' ')?;
+    consumed.parse().ok()
+}
+
+/// `Program {program_id} success` or `Program {program_id} failed: {err}`
+fn parse_result(line: &str, program_id: &Pubkey) -> Option<Result<(), String>> {
+    let rest = line
+        .strip_prefix("Program ")?
+        .strip_prefix(program_id.to_string().as_str())?;
+    if rest == " success" {
+        Some(Ok(()))
+    } else {
+        rest.strip_prefix(" failed: ").map(|e| Err(e.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::system_program;
+
+    use super::*;
+
+    fn sample_logs() -> Vec<String> {
+        let outer = Pubkey::new_from_array([1; 32]);
+        let inner = Pubkey::new_from_array([2; 32]);
+        vec![
+            format!("Program {outer} invoke [1]"),
+            "Program log: Instruction: Swap".to_owned(),
+            format!("Program {inner} invoke [2]"),
+            "Program log: Instruction: Transfer".to_owned(),
+            format!("Program {inner} consumed 4645 of 180000 compute units"),
+            format!("Program return: {inner} AQID"),
+            format!("Program {inner} success"),
+            "Program data: AQID BAU=".to_owned(),
+            format!("Program {outer} consumed 25000 of 200000 compute units"),
+            format!("Program {outer} success"),
+            format!("Program {} invoke [1]", system_program::ID),
+            format!("Program {} success", system_program::ID),
+            format!("Program {outer} invoke [1]"),
+            format!("Program {outer} consumed 100 of 175000 compute units"),
+            format!("Program {outer} failed: custom program error: 0x1"),
+        ]
+    }
+
+    #[test]
+    fn parse_tree() {
+        let logs = ParsedLogs::parse(&sample_logs());
+        assert!(!logs.truncated);
+        assert_eq!(logs.instructions.len(), 3);
+
+        let swap = logs.instruction(0);
+        assert!(swap.succeeded());
+        assert_eq!(swap.depth, 1);
+        assert_eq!(swap.compute_units_consumed, Some(25000));
+        assert_eq!(swap.data, vec![vec![vec![1, 2, 3], vec![4, 5]]]);
+        assert_eq!(swap.inner.len(), 1);
+        let transfer = &swap.inner[0];
+        assert_eq!(transfer.depth, 2);
+        assert_eq!(transfer.return_data, Some(vec![1, 2, 3]));
+        transfer.assert_logged("Transfer");
+        transfer.assert_cu_below(5000);
+
+        assert_eq!(logs.instruction(1).compute_units_consumed, None);
+        assert_eq!(
+            logs.instruction(2).result,
+            Some(Err("custom program error: 0x1".to_owned()))
+        );
+
+        logs.assert_logged("Swap");
+        logs.assert_cu_below(25001);
+        logs.assert_invoked_program(&Pubkey::new_from_array([2; 32]));
+        assert_eq!(logs.iter().count(), 4);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let mut lines = sample_logs();
+        lines.truncate(4);
+        lines.push("Log truncated".to_owned());
+        let logs = ParsedLogs::parse(&lines);
+        assert!(logs.truncated);
+        assert_eq!(logs.instructions.len(), 1);
+        assert_eq!(logs.instruction(0).result, None);
+        assert_eq!(logs.instruction(0).inner[0].result, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "consumed 25000 CUs")]
+    fn assert_cu_below_fails() {
+        ParsedLogs::parse(&sample_logs()).assert_cu_below(20_000);
+    }
+}
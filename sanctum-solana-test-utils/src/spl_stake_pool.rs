@@ -0,0 +1,688 @@
+use std::num::NonZeroU32;
+
+use borsh::BorshSerialize;
+use sanctum_spl_stake_pool_lib::{
+    FindDepositAuthority, FindTransientStakeAccount, FindTransientStakeAccountArgs,
+    FindValidatorStakeAccount, FindValidatorStakeAccountArgs, FindWithdrawAuthority,
+    STAKE_POOL_SIZE, ZERO_FEE,
+};
+use solana_program::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, stake::state::Meta, stake::state::StakeStateV2,
+};
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::account::Account;
+use spl_stake_pool_interface::{
+    AccountType, Fee, FutureEpochFee, Lockup, StakePool, StakeStatus, ValidatorList,
+    ValidatorListHeader, ValidatorStakeInfo,
+};
+
+use crate::{
+    est_rent_exempt_lamports,
+    stake::{
+        SingleAuthorityAuthorized, StakeAccountFixtureBuilder, StakeLifecycle,
+        StakeStateAndLamports,
+    },
+    token::{
+        tokenkeg::{mock_tokenkeg_account, mock_tokenkeg_mint},
+        MockMintArgs, MockTokenAccountArgs,
+    },
+    ExtendedProgramTest, IntoAccount,
+};
+
+/// Borsh-serialized size of [`ValidatorStakeInfo`]
+pub const VALIDATOR_STAKE_INFO_SIZE: usize = 73;
+
+/// Size of the [`ValidatorListHeader`] and the u32 length prefix of the validators vec
+pub const VALIDATOR_LIST_HEADER_SIZE: usize = 9;
+
+/// Size of a validator list account that can hold up to `max_validators` validators
+pub const fn validator_list_size(max_validators: u32) -> usize {
+    VALIDATOR_LIST_HEADER_SIZE + VALIDATOR_STAKE_INFO_SIZE * max_validators as usize
+}
+
+/// A validator in a [`StakePoolFixtureBuilder`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakePoolFixtureValidator {
+    pub vote: Pubkey,
+
+    /// Active stake of the validator stake account, excluding its rent-exempt reserve
+    pub active_staked_lamports: u64,
+
+    /// Activating stake of the transient stake account, excluding its rent-exempt reserve.
+    ///
+    /// The transient stake account is not created if 0
+    pub transient_staked_lamports: u64,
+
+    pub validator_seed_suffix: Option<NonZeroU32>,
+    pub transient_seed_suffix: u64,
+}
+
+impl StakePoolFixtureValidator {
+    /// A validator with no transient stake and no seed suffixes
+    pub const fn new(vote: Pubkey, active_staked_lamports: u64) -> Self {
+        Self {
+            vote,
+            active_staked_lamports,
+            transient_staked_lamports: 0,
+            validator_seed_suffix: None,
+            transient_seed_suffix: 0,
+        }
+    }
+}
+
+/// All the accounts of a stake pool built by [`StakePoolFixtureBuilder`]
+#[derive(Clone, Debug)]
+pub struct StakePoolFixture {
+    pub stake_pool: Keyed<Account>,
+    pub validator_list: Keyed<Account>,
+    pub reserve_stake: Keyed<Account>,
+    pub pool_mint: Keyed<Account>,
+    pub manager_fee_account: Keyed<Account>,
+
+    /// Same order as the validator list
+    pub validator_stake_accounts: Vec<Keyed<Account>>,
+
+    /// Only validators with transient stake have one
+    pub transient_stake_accounts: Vec<Keyed<Account>>,
+}
+
+impl StakePoolFixture {
+    pub fn into_keyed_accounts(self) -> Vec<Keyed<Account>> {
+        [
+            self.stake_pool,
+            self.validator_list,
+            self.reserve_stake,
+            self.pool_mint,
+            self.manager_fee_account,
+        ]
+        .into_iter()
+        .chain(self.validator_stake_accounts)
+        .chain(self.transient_stake_accounts)
+        .collect()
+    }
+}
+
+/// Builds the accounts of an up-to-date SPL stake pool that uses the tokenkeg program.
+///
+/// Validator stake accounts are active and transient stake accounts are activating
+/// at `last_update_epoch`, see [`StakeAccountFixtureBuilder`].
+/// The pool's `total_lamports` is the sum of the stake accounts' lamports,
+/// excluding the reserve's rent-exempt reserve.
+///
+/// Addresses of the non-PDA accounts are derived from the stake pool's address
+/// with `Pubkey::create_with_seed()` so that fixtures are deterministic
+#[derive(Clone, Debug)]
+pub struct StakePoolFixtureBuilder {
+    program_id: Pubkey,
+    stake_pool: Pubkey,
+    manager: Pubkey,
+    staker: Pubkey,
+    reserve_lamports: u64,
+    validators: Vec<StakePoolFixtureValidator>,
+    max_validators: Option<u32>,
+    pool_token_supply: Option<u64>,
+    last_update_epoch: u64,
+    epoch_fee: Fee,
+    stake_deposit_fee: Fee,
+    stake_withdrawal_fee: Fee,
+    sol_deposit_fee: Fee,
+    sol_withdrawal_fee: Fee,
+    stake_referral_fee: u8,
+    sol_referral_fee: u8,
+    preferred_deposit_validator: Option<Pubkey>,
+    preferred_withdraw_validator: Option<Pubkey>,
+}
+
+impl StakePoolFixtureBuilder {
+    /// Defaults to a pool with no validators, zero fees,
+    /// and 1 SOL in the reserve at epoch 0
+    pub fn new(program_id: Pubkey, stake_pool: Pubkey) -> Self {
+        Self {
+            program_id,
+            stake_pool,
+            manager: Pubkey::default(),
+            staker: Pubkey::default(),
+            reserve_lamports: LAMPORTS_PER_SOL,
+            validators: Vec::new(),
+            max_validators: None,
+            pool_token_supply: None,
+            last_update_epoch: 0,
+            epoch_fee: ZERO_FEE,
+            stake_deposit_fee: ZERO_FEE,
+            stake_withdrawal_fee: ZERO_FEE,
+            sol_deposit_fee: ZERO_FEE,
+            sol_withdrawal_fee: ZERO_FEE,
+            stake_referral_fee: 0,
+            sol_referral_fee: 0,
+            preferred_deposit_validator: None,
+            preferred_withdraw_validator: None,
+        }
+    }
+
+    /// Defaults to the canonical SPL stake pool program
+    pub fn spl(stake_pool: Pubkey) -> Self {
+        Self::new(spl_stake_pool_interface::ID, stake_pool)
+    }
+
+    pub fn manager(mut self, manager: Pubkey) -> Self {
+        self.manager = manager;
+        self
+    }
+
+    pub fn staker(mut self, staker: Pubkey) -> Self {
+        self.staker = staker;
+        self
+    }
+
+    /// Lamports in the reserve on top of its rent-exempt reserve
+    pub fn reserve_lamports(mut self, reserve_lamports: u64) -> Self {
+        self.reserve_lamports = reserve_lamports;
+        self
+    }
+
+    pub fn validator(mut self, validator: StakePoolFixtureValidator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Adds `n` validators with deterministic vote accounts,
+    /// each with `active_staked_lamports` of active stake
+    pub fn n_validators(self, n: usize, active_staked_lamports: u64) -> Self {
+        let start = self.validators.len();
+        (start..start + n).fold(self, |builder, i| {
+            let vote = builder.derived_address(&format!("vote{i}"));
+            builder.validator(StakePoolFixtureValidator::new(vote, active_staked_lamports))
+        })
+    }
+
+    /// Defaults to the number of validators.
+    /// [`Self::build`] panics if this is less than the number of validators
+    pub fn max_validators(mut self, max_validators: u32) -> Self {
+        self.max_validators = Some(max_validators);
+        self
+    }
+
+    /// Defaults to `total_lamports`, i.e. 1 pool token = 1 lamport
+    pub fn pool_token_supply(mut self, pool_token_supply: u64) -> Self {
+        self.pool_token_supply = Some(pool_token_supply);
+        self
+    }
+
+    pub fn last_update_epoch(mut self, last_update_epoch: u64) -> Self {
+        self.last_update_epoch = last_update_epoch;
+        self
+    }
+
+    pub fn epoch_fee(mut self, fee: Fee) -> Self {
+        self.epoch_fee = fee;
+        self
+    }
+
+    pub fn stake_deposit_fee(mut self, fee: Fee) -> Self {
+        self.stake_deposit_fee = fee;
+        self
+    }
+
+    pub fn stake_withdrawal_fee(mut self, fee: Fee) -> Self {
+        self.stake_withdrawal_fee = fee;
+        self
+    }
+
+    pub fn sol_deposit_fee(mut self, fee: Fee) -> Self {
+        self.sol_deposit_fee = fee;
+        self
+    }
+
+    pub fn sol_withdrawal_fee(mut self, fee: Fee) -> Self {
+        self.sol_withdrawal_fee = fee;
+        self
+    }
+
+    /// Percentage of the deposit fee that goes to the referrer
+    pub fn stake_referral_fee(mut self, pct: u8) -> Self {
+        self.stake_referral_fee = pct;
+        self
+    }
+
+    /// Percentage of the deposit fee that goes to the referrer
+    pub fn sol_referral_fee(mut self, pct: u8) -> Self {
+        self.sol_referral_fee = pct;
+        self
+    }
+
+    pub fn preferred_deposit_validator(mut self, vote: Option<Pubkey>) -> Self {
+        self.preferred_deposit_validator = vote;
+        self
+    }
+
+    pub fn preferred_withdraw_validator(mut self, vote: Option<Pubkey>) -> Self {
+        self.preferred_withdraw_validator = vote;
+        self
+    }
+
+    fn derived_address(&self, seed: &str) -> Pubkey {
+        Pubkey::create_with_seed(&self.stake_pool, seed, &self.program_id).unwrap()
+    }
+
+    pub fn validator_list_addr(&self) -> Pubkey {
+        self.derived_address("validator_list")
+    }
+
+    pub fn reserve_stake_addr(&self) -> Pubkey {
+        self.derived_address("reserve_stake")
+    }
+
+    pub fn pool_mint_addr(&self) -> Pubkey {
+        self.derived_address("pool_mint")
+    }
+
+    pub fn manager_fee_account_addr(&self) -> Pubkey {
+        self.derived_address("manager_fee_account")
+    }
+
+    pub fn withdraw_authority(&self) -> (Pubkey, u8) {
+        FindWithdrawAuthority {
+            pool: self.stake_pool,
+        }
+        .run_for_prog(&self.program_id)
+    }
+
+    pub fn validator_stake_account_addr(&self, validator: &StakePoolFixtureValidator) -> Pubkey {
+        FindValidatorStakeAccount::new(FindValidatorStakeAccountArgs {
+            pool: self.stake_pool,
+            vote: validator.vote,
+            seed: validator.validator_seed_suffix,
+        })
+        .run_for_prog(&self.program_id)
+        .0
+    }
+
+    pub fn transient_stake_account_addr(&self, validator: &StakePoolFixtureValidator) -> Pubkey {
+        FindTransientStakeAccount::new(FindTransientStakeAccountArgs {
+            pool: self.stake_pool,
+            vote: validator.vote,
+            seed: validator.transient_seed_suffix,
+        })
+        .run_for_prog(&self.program_id)
+        .0
+    }
+
+    fn pool_stake_account(
+        &self,
+        lifecycle: StakeLifecycle,
+        vote: Pubkey,
+        staked_lamports: u64,
+    ) -> StakeAccountFixtureBuilder {
+        StakeAccountFixtureBuilder::new(lifecycle, self.last_update_epoch)
+            .voter(vote)
+            .authorized(SingleAuthorityAuthorized(self.withdraw_authority().0))
+            .staked_lamports(staked_lamports)
+    }
+
+    /// ## Panics
+    /// If [`Self::max_validators`] is less than the number of validators
+    pub fn build(&self) -> StakePoolFixture {
+        let (withdraw_authority, stake_withdraw_bump_seed) = self.withdraw_authority();
+        let stake_rent = StakeAccountFixtureBuilder::rent_exempt_reserve();
+
+        let mut validator_stake_accounts = Vec::new();
+        let mut transient_stake_accounts = Vec::new();
+        let mut validator_infos = Vec::new();
+        for v in self.validators.iter() {
+            let active_stake_lamports = stake_rent + v.active_staked_lamports;
+            validator_stake_accounts.push(Keyed {
+                pubkey: self.validator_stake_account_addr(v),
+                account: self
+                    .pool_stake_account(StakeLifecycle::Active, v.vote, v.active_staked_lamports)
+                    .into_account(),
+            });
+            let transient_stake_lamports = if v.transient_staked_lamports == 0 {
+                0
+            } else {
+                transient_stake_accounts.push(Keyed {
+                    pubkey: self.transient_stake_account_addr(v),
+                    account: self
+                        .pool_stake_account(
+                            StakeLifecycle::Activating,
+                            v.vote,
+                            v.transient_staked_lamports,
+                        )
+                        .into_account(),
+                });
+                stake_rent + v.transient_staked_lamports
+            };
+            validator_infos.push(ValidatorStakeInfo {
+                active_stake_lamports,
+                transient_stake_lamports,
+                last_update_epoch: self.last_update_epoch,
+                transient_seed_suffix: v.transient_seed_suffix,
+                unused: 0,
+                validator_seed_suffix: v.validator_seed_suffix.map_or(0, |s| s.get()),
+                status: StakeStatus::Active,
+                vote_account_address: v.vote,
+            });
+        }
+
+        let total_lamports = validator_infos
+            .iter()
+            .fold(self.reserve_lamports, |sum, v| {
+                sum + v.active_stake_lamports + v.transient_stake_lamports
+            });
+        let pool_token_supply = self.pool_token_supply.unwrap_or(total_lamports);
+
+        let reserve_stake = StakeStateAndLamports {
+            stake_state: StakeStateV2::Initialized(Meta {
+                rent_exempt_reserve: stake_rent,
+                authorized: SingleAuthorityAuthorized(withdraw_authority).into(),
+                lockup: Default::default(),
+            }),
+            total_lamports: stake_rent + self.reserve_lamports,
+        };
+
+        let max_validators = self
+            .max_validators
+            .unwrap_or(self.validators.len().try_into().unwrap());
+        assert!(
+            self.validators.len() <= max_validators as usize,
+            "max_validators {max_validators} less than number of validators {}",
+            self.validators.len()
+        );
+        let mut validator_list_data = vec![0u8; validator_list_size(max_validators)];
+        ValidatorList {
+            header: ValidatorListHeader {
+                account_type: AccountType::ValidatorList,
+                max_validators,
+            },
+            validators: validator_infos,
+        }
+        .serialize(&mut validator_list_data.as_mut_slice())
+        .unwrap();
+
+        let stake_pool = StakePool {
+            account_type: AccountType::StakePool,
+            manager: self.manager,
+            staker: self.staker,
+            stake_deposit_authority: FindDepositAuthority {
+                pool: self.stake_pool,
+            }
+            .run_for_prog(&self.program_id)
+            .0,
+            stake_withdraw_bump_seed,
+            validator_list: self.validator_list_addr(),
+            reserve_stake: self.reserve_stake_addr(),
+            pool_mint: self.pool_mint_addr(),
+            manager_fee_account: self.manager_fee_account_addr(),
+            token_program: spl_token::ID,
+            total_lamports,
+            pool_token_supply,
+            last_update_epoch: self.last_update_epoch,
+            lockup: Lockup {
+                unix_timestamp: 0,
+                epoch: 0,
+                custodian: Pubkey::default(),
+            },
+            epoch_fee: self.epoch_fee.clone(),
+            next_epoch_fee: FutureEpochFee::None,
+            preferred_deposit_validator_vote_address: self.preferred_deposit_validator,
+            preferred_withdraw_validator_vote_address: self.preferred_withdraw_validator,
+            stake_deposit_fee: self.stake_deposit_fee.clone(),
+            stake_withdrawal_fee: self.stake_withdrawal_fee.clone(),
+            next_stake_withdrawal_fee: FutureEpochFee::None,
+            stake_referral_fee: self.stake_referral_fee,
+            sol_deposit_authority: None,
+            sol_deposit_fee: self.sol_deposit_fee.clone(),
+            sol_referral_fee: self.sol_referral_fee,
+            sol_withdraw_authority: None,
+            sol_withdrawal_fee: self.sol_withdrawal_fee.clone(),
+            next_sol_withdrawal_fee: FutureEpochFee::None,
+            last_epoch_pool_token_supply: pool_token_supply,
+            last_epoch_total_lamports: total_lamports,
+        };
+
+        let mut stake_pool_data = vec![0u8; STAKE_POOL_SIZE];
+        stake_pool
+            .serialize(&mut stake_pool_data.as_mut_slice())
+            .unwrap();
+
+        StakePoolFixture {
+            stake_pool: Keyed {
+                pubkey: self.stake_pool,
+                account: program_account(self.program_id, stake_pool_data),
+            },
+            validator_list: Keyed {
+                pubkey: self.validator_list_addr(),
+                account: program_account(self.program_id, validator_list_data),
+            },
+            reserve_stake: Keyed {
+                pubkey: self.reserve_stake_addr(),
+                account: reserve_stake.into_account(),
+            },
+            pool_mint: Keyed {
+                pubkey: self.pool_mint_addr(),
+                account: mock_tokenkeg_mint(MockMintArgs {
+                    mint_authority: Some(withdraw_authority),
+                    freeze_authority: None,
+                    supply: pool_token_supply,
+                    decimals: 9,
+                })
+                .into_account(),
+            },
+            manager_fee_account: Keyed {
+                pubkey: self.manager_fee_account_addr(),
+                account: mock_tokenkeg_account(MockTokenAccountArgs {
+                    mint: self.pool_mint_addr(),
+                    authority: self.manager,
+                    amount: 0,
+                })
+                .into_account(),
+            },
+            validator_stake_accounts,
+            transient_stake_accounts,
+        }
+    }
+}
+
+fn program_account(program_id: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: est_rent_exempt_lamports(data.len()),
+        data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
+
+pub trait SplStakePoolProgramTest {
+    fn add_stake_pool_fixture(self, fixture: StakePoolFixture) -> Self;
+}
+
+impl<T: ExtendedProgramTest> SplStakePoolProgramTest for T {
+    fn add_stake_pool_fixture(self, fixture: StakePoolFixture) -> Self {
+        fixture
+            .into_keyed_accounts()
+            .into_iter()
+            .fold(self, |pt, keyed| pt.add_keyed_account(keyed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sanctum_spl_stake_pool_lib::{
+        account_resolvers::UpdateStakePoolBalance, deserialize_stake_pool_checked,
+        deserialize_validator_list_checked, QuoteDepositStake, StakeAccountDataForQuoting,
+    };
+    use solana_program_test::ProgramTest;
+
+    use crate::ExtendedBanksClient;
+
+    use super::*;
+
+    #[test]
+    fn lamport_totals_consistent() {
+        let pool = Pubkey::new_unique();
+        let preferred = Pubkey::new_unique();
+        let builder = StakePoolFixtureBuilder::spl(pool)
+            .n_validators(3, 10 * LAMPORTS_PER_SOL)
+            .validator(StakePoolFixtureValidator {
+                vote: preferred,
+                active_staked_lamports: 5 * LAMPORTS_PER_SOL,
+                transient_staked_lamports: 2 * LAMPORTS_PER_SOL,
+                validator_seed_suffix: NonZeroU32::new(1),
+                transient_seed_suffix: 7,
+            })
+            .max_validators(10)
+            .preferred_deposit_validator(Some(preferred))
+            .epoch_fee(Fee {
+                denominator: 100,
+                numerator: 5,
+            })
+            .last_update_epoch(3);
+        let fixture = builder.build();
+
+        let sp = deserialize_stake_pool_checked(&fixture.stake_pool.account.data).unwrap();
+        let vl = deserialize_validator_list_checked(&fixture.validator_list.account.data).unwrap();
+        assert_eq!(
+            fixture.validator_list.account.data.len(),
+            validator_list_size(10)
+        );
+        assert_eq!(vl.validators.len(), 4);
+        assert_eq!(sp.preferred_deposit_validator_vote_address, Some(preferred));
+        assert_eq!(sp.last_update_epoch, 3);
+        assert_eq!(sp.validator_list, fixture.validator_list.pubkey);
+        assert_eq!(sp.reserve_stake, fixture.reserve_stake.pubkey);
+        assert_eq!(sp.pool_mint, fixture.pool_mint.pubkey);
+        assert_eq!(sp.manager_fee_account, fixture.manager_fee_account.pubkey);
+
+        for (info, vsa) in vl
+            .validators
+            .iter()
+            .zip(fixture.validator_stake_accounts.iter())
+        {
+            assert_eq!(info.active_stake_lamports, vsa.account.lamports);
+        }
+        assert_eq!(fixture.transient_stake_accounts.len(), 1);
+        assert_eq!(
+            vl.validators[3].transient_stake_lamports,
+            fixture.transient_stake_accounts[0].account.lamports
+        );
+        assert_eq!(
+            fixture.transient_stake_accounts[0].pubkey,
+            builder.transient_stake_account_addr(&StakePoolFixtureValidator {
+                vote: preferred,
+                active_staked_lamports: 0,
+                transient_staked_lamports: 0,
+                validator_seed_suffix: None,
+                transient_seed_suffix: 7,
+            })
+        );
+
+        let stake_accounts_lamports: u64 = fixture
+            .validator_stake_accounts
+            .iter()
+            .chain(fixture.transient_stake_accounts.iter())
+            .map(|k| k.account.lamports)
+            .sum();
+        assert_eq!(
+            sp.total_lamports,
+            stake_accounts_lamports + fixture.reserve_stake.account.lamports
+                - StakeAccountFixtureBuilder::rent_exempt_reserve()
+        );
+        assert_eq!(sp.pool_token_supply, sp.total_lamports);
+    }
+
+    #[test]
+    fn fixture_usable_by_lib() {
+        let pool = Pubkey::new_unique();
+        let builder = StakePoolFixtureBuilder::spl(pool)
+            .n_validators(2, 10 * LAMPORTS_PER_SOL)
+            .stake_deposit_fee(Fee {
+                denominator: 100,
+                numerator: 1,
+            })
+            .stake_referral_fee(50);
+        let fixture = builder.build();
+
+        let keys = UpdateStakePoolBalance {
+            stake_pool: &fixture.stake_pool,
+        }
+        .resolve_for_prog(&spl_stake_pool_interface::ID)
+        .unwrap();
+        assert_eq!(keys.stake_pool, pool);
+        assert_eq!(keys.withdraw_authority, builder.withdraw_authority().0);
+        assert_eq!(keys.validator_list, fixture.validator_list.pubkey);
+        assert_eq!(keys.reserve_stake, fixture.reserve_stake.pubkey);
+        assert_eq!(keys.manager_fee_account, fixture.manager_fee_account.pubkey);
+        assert_eq!(keys.pool_mint, fixture.pool_mint.pubkey);
+        assert_eq!(keys.token_program, fixture.pool_mint.account.owner);
+
+        // pool tokens are 1:1 with lamports, so only fees are deducted.
+        // The stake deposit fee only applies to the staked lamports,
+        // the rent-exempt reserve is charged the zero sol deposit fee
+        let vsa = &fixture.validator_stake_accounts[0];
+        let deposit = StakeAccountDataForQuoting::from_stake_account(vsa).unwrap();
+        assert_eq!(deposit.staked_lamports, 10 * LAMPORTS_PER_SOL);
+        let sp = deserialize_stake_pool_checked(&fixture.stake_pool.account.data).unwrap();
+        let quote = sp.quote_deposit_stake(&deposit).unwrap();
+        let fee = deposit.staked_lamports.div_ceil(100);
+        assert_eq!(quote.user, vsa.account.lamports - fee);
+        assert_eq!(quote.manager + quote.referrer, fee);
+        assert_eq!(quote.referrer, fee / 2);
+    }
+
+    #[test]
+    fn validator_list_sizes_match_borsh() {
+        let header = ValidatorListHeader {
+            account_type: AccountType::ValidatorList,
+            max_validators: 1,
+        };
+        let info = ValidatorStakeInfo {
+            active_stake_lamports: 1,
+            transient_stake_lamports: 2,
+            last_update_epoch: 3,
+            transient_seed_suffix: 4,
+            unused: 0,
+            validator_seed_suffix: 5,
+            status: StakeStatus::Active,
+            vote_account_address: Pubkey::new_unique(),
+        };
+        assert_eq!(info.try_to_vec().unwrap().len(), VALIDATOR_STAKE_INFO_SIZE);
+        let empty = ValidatorList {
+            header: header.clone(),
+            validators: vec![],
+        };
+        assert_eq!(
+            empty.try_to_vec().unwrap().len(),
+            VALIDATOR_LIST_HEADER_SIZE
+        );
+        let full = ValidatorList {
+            header,
+            validators: vec![info],
+        };
+        assert_eq!(full.try_to_vec().unwrap().len(), validator_list_size(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_validators 1 less than number of validators 2")]
+    fn max_validators_less_than_validators() {
+        StakePoolFixtureBuilder::spl(Pubkey::new_unique())
+            .n_validators(2, LAMPORTS_PER_SOL)
+            .max_validators(1)
+            .build();
+    }
+
+    #[tokio::test]
+    async fn add_to_program_test() {
+        let pool = Pubkey::new_unique();
+        let fixture = StakePoolFixtureBuilder::spl(pool)
+            .n_validators(2, LAMPORTS_PER_SOL)
+            .build();
+        let expected = fixture.clone().into_keyed_accounts();
+        let (mut bc, _payer, _rbh) = ProgramTest::default()
+            .add_stake_pool_fixture(fixture)
+            .start()
+            .await;
+        for Keyed { pubkey, account } in expected {
+            assert_eq!(bc.get_account_unwrapped(pubkey).await, account);
+        }
+    }
+}
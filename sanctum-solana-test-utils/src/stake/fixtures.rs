@@ -0,0 +1,384 @@
+use solana_program::{
+    clock::Epoch,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    stake::state::{Authorized, Delegation, Lockup, Meta, Stake, StakeStateV2},
+    stake_history::{StakeHistory, StakeHistoryEntry, MAX_ENTRIES},
+    sysvar,
+};
+use solana_sdk::{account::Account, stake::stake_flags::StakeFlags};
+
+use crate::{est_rent_exempt_lamports, IntoAccount};
+
+use super::StakeStateAndLamports;
+
+/// `ProgramTest` activates all features at genesis,
+/// including the one that reduces the warmup/cooldown rate
+pub const NEW_RATE_ACTIVATION_EPOCH: Option<Epoch> = Some(0);
+
+/// Roughly the effective stake of mainnet-beta
+pub const DEFAULT_CLUSTER_EFFECTIVE_STAKE: u64 = 400_000_000 * LAMPORTS_PER_SOL;
+
+/// The state of a stake account as seen by the stake program at an epoch
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StakeLifecycle {
+    /// Funded but not yet initialized
+    Uninitialized,
+
+    /// Initialized but never delegated
+    Initialized,
+
+    /// Delegated this epoch, no effective stake yet
+    Activating,
+
+    /// Fully effective
+    Active,
+
+    /// Deactivated this epoch, still fully effective
+    Deactivating,
+
+    /// Deactivated in a previous epoch, no effective stake left
+    Deactivated,
+}
+
+/// Builds a stake account that is in `lifecycle` at `current_epoch`.
+///
+/// Warmup and cooldown are assumed to complete within an epoch,
+/// which holds if the `StakeHistory` sysvar is that built by a [`StakeHistoryFixtureBuilder`]
+/// with the same `current_epoch`, or if it doesn't have entries for the delegation's epochs.
+///
+/// At epoch 0, [`StakeLifecycle::Active`] and [`StakeLifecycle::Deactivating`] stake accounts
+/// are bootstrap stakes and [`StakeLifecycle::Deactivated`] stake accounts are
+/// deactivated in the same epoch they were activated in
+#[derive(Clone, Copy, Debug)]
+pub struct StakeAccountFixtureBuilder {
+    lifecycle: StakeLifecycle,
+    current_epoch: Epoch,
+    staked_lamports: u64,
+    voter: Pubkey,
+    authorized: Authorized,
+    lockup: Lockup,
+    credits_observed: u64,
+}
+
+impl StakeAccountFixtureBuilder {
+    /// Defaults to 1 SOL staked to the default pubkey with default authorities and no lockup
+    pub fn new(lifecycle: StakeLifecycle, current_epoch: Epoch) -> Self {
+        Self {
+            lifecycle,
+            current_epoch,
+            staked_lamports: LAMPORTS_PER_SOL,
+            voter: Pubkey::default(),
+            authorized: Authorized::default(),
+            lockup: Lockup::default(),
+            credits_observed: 0,
+        }
+    }
+
+    /// Lamports in the account on top of the rent-exempt reserve
+    pub fn staked_lamports(mut self, staked_lamports: u64) -> Self {
+        self.staked_lamports = staked_lamports;
+        self
+    }
+
+    pub fn voter(mut self, voter: Pubkey) -> Self {
+        self.voter = voter;
+        self
+    }
+
+    pub fn authorized(mut self, authorized: impl Into<Authorized>) -> Self {
+        self.authorized = authorized.into();
+        self
+    }
+
+    pub fn lockup(mut self, lockup: Lockup) -> Self {
+        self.lockup = lockup;
+        self
+    }
+
+    pub fn credits_observed(mut self, credits_observed: u64) -> Self {
+        self.credits_observed = credits_observed;
+        self
+    }
+
+    pub fn lifecycle(&self) -> StakeLifecycle {
+        self.lifecycle
+    }
+
+    pub fn current_epoch(&self) -> Epoch {
+        self.current_epoch
+    }
+
+    pub fn rent_exempt_reserve() -> u64 {
+        est_rent_exempt_lamports(StakeStateV2::size_of())
+    }
+
+    /// `None` if the stake account is not delegated
+    pub fn delegation(&self) -> Option<Delegation> {
+        // bootstrap stake if there's no previous epoch
+        let prev_epoch = self.current_epoch.checked_sub(1).unwrap_or(u64::MAX);
+        let (activation_epoch, deactivation_epoch) = match self.lifecycle {
+            StakeLifecycle::Uninitialized | StakeLifecycle::Initialized => return None,
+            StakeLifecycle::Activating => (self.current_epoch, u64::MAX),
+            StakeLifecycle::Active => (prev_epoch, u64::MAX),
+            StakeLifecycle::Deactivating => (prev_epoch, self.current_epoch),
+            StakeLifecycle::Deactivated => {
+                let deactivation_epoch = self.current_epoch.saturating_sub(1);
+                (deactivation_epoch.saturating_sub(1), deactivation_epoch)
+            }
+        };
+        Some(Delegation {
+            voter_pubkey: self.voter,
+            stake: self.staked_lamports,
+            activation_epoch,
+            deactivation_epoch,
+            ..Default::default()
+        })
+    }
+
+    pub fn build(&self) -> StakeStateAndLamports {
+        let rent_exempt_reserve = Self::rent_exempt_reserve();
+        let meta = Meta {
+            rent_exempt_reserve,
+            authorized: self.authorized,
+            lockup: self.lockup,
+        };
+        let stake_state = match (self.lifecycle, self.delegation()) {
+            (StakeLifecycle::Uninitialized, _) => StakeStateV2::Uninitialized,
+            (_, None) => StakeStateV2::Initialized(meta),
+            (_, Some(delegation)) => StakeStateV2::Stake(
+                meta,
+                Stake {
+                    delegation,
+                    credits_observed: self.credits_observed,
+                },
+                StakeFlags::empty(),
+            ),
+        };
+        StakeStateAndLamports {
+            stake_state,
+            total_lamports: rent_exempt_reserve + self.staked_lamports,
+        }
+    }
+}
+
+impl IntoAccount for StakeAccountFixtureBuilder {
+    fn into_account(self) -> Account {
+        self.build().into_account()
+    }
+}
+
+/// Builds the `StakeHistory` sysvar as of `current_epoch` for a cluster with
+/// the given delegations and `cluster_effective_stake` of other fully effective stake,
+/// the same way the bank computes it at each epoch boundary.
+///
+/// The default `cluster_effective_stake` is large enough for delegations
+/// totalling < 36M SOL per epoch to warm up or cool down in a single epoch
+///
+/// `ProgramTest` overwrites the sysvar at genesis and at every epoch boundary,
+/// so set it with `ProgramTestContext::set_sysvar()` after warping
+#[derive(Clone, Debug)]
+pub struct StakeHistoryFixtureBuilder {
+    current_epoch: Epoch,
+    cluster_effective_stake: u64,
+    delegations: Vec<Delegation>,
+}
+
+impl StakeHistoryFixtureBuilder {
+    pub fn new(current_epoch: Epoch) -> Self {
+        Self {
+            current_epoch,
+            cluster_effective_stake: DEFAULT_CLUSTER_EFFECTIVE_STAKE,
+            delegations: Vec::new(),
+        }
+    }
+
+    pub fn cluster_effective_stake(mut self, cluster_effective_stake: u64) -> Self {
+        self.cluster_effective_stake = cluster_effective_stake;
+        self
+    }
+
+    pub fn delegation(mut self, delegation: Delegation) -> Self {
+        self.delegations.push(delegation);
+        self
+    }
+
+    /// No-op if the stake account is not delegated
+    pub fn stake_account(self, stake_account: &StakeAccountFixtureBuilder) -> Self {
+        match stake_account.delegation() {
+            Some(d) => self.delegation(d),
+            None => self,
+        }
+    }
+
+    pub fn stake_accounts<'a>(
+        self,
+        stake_accounts: impl IntoIterator<Item = &'a StakeAccountFixtureBuilder>,
+    ) -> Self {
+        stake_accounts
+            .into_iter()
+            .fold(self, |builder, s| builder.stake_account(s))
+    }
+
+    /// Entries for up to [`MAX_ENTRIES`] epochs before `current_epoch`
+    pub fn build(&self) -> StakeHistory {
+        let mut history = StakeHistory::default();
+        let first_epoch = self.current_epoch.saturating_sub(MAX_ENTRIES as u64);
+        for epoch in first_epoch..self.current_epoch {
+            let entry = self.delegations.iter().fold(
+                StakeHistoryEntry::with_effective(self.cluster_effective_stake),
+                |entry, d| {
+                    let status = d.stake_activating_and_deactivating(
+                        epoch,
+                        &history,
+                        NEW_RATE_ACTIVATION_EPOCH,
+                    );
+                    entry
+                        + StakeHistoryEntry {
+                            effective: status.effective,
+                            activating: status.activating,
+                            deactivating: status.deactivating,
+                        }
+                },
+            );
+            history.add(epoch, entry);
+        }
+        history
+    }
+}
+
+impl IntoAccount for StakeHistoryFixtureBuilder {
+    fn into_account(self) -> Account {
+        let data = bincode::serialize(&self.build()).unwrap();
+        Account {
+            lamports: est_rent_exempt_lamports(data.len()),
+            data,
+            owner: sysvar::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::stake::{instruction::withdraw, state::StakeActivationStatus};
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{
+        signature::Keypair, signer::Signer, transaction::Transaction, transaction::TransactionError,
+    };
+
+    use crate::stake::SingleAuthorityAuthorized;
+
+    use super::*;
+
+    const ALL_LIFECYCLES: [StakeLifecycle; 6] = [
+        StakeLifecycle::Uninitialized,
+        StakeLifecycle::Initialized,
+        StakeLifecycle::Activating,
+        StakeLifecycle::Active,
+        StakeLifecycle::Deactivating,
+        StakeLifecycle::Deactivated,
+    ];
+
+    fn expected_status(lifecycle: StakeLifecycle, stake: u64) -> StakeActivationStatus {
+        match lifecycle {
+            StakeLifecycle::Activating => {
+                StakeActivationStatus::with_effective_and_activating(0, stake)
+            }
+            StakeLifecycle::Active => StakeActivationStatus::with_effective(stake),
+            StakeLifecycle::Deactivating => StakeActivationStatus::with_deactivating(stake),
+            _ => StakeActivationStatus::default(),
+        }
+    }
+
+    #[test]
+    fn lifecycle_matches_stake_history() {
+        for current_epoch in [0, 1, 2, 10, 600] {
+            let stake_accounts: Vec<_> = ALL_LIFECYCLES
+                .iter()
+                .map(|l| {
+                    StakeAccountFixtureBuilder::new(*l, current_epoch)
+                        .staked_lamports(1_000_000 * LAMPORTS_PER_SOL)
+                })
+                .collect();
+            let history = StakeHistoryFixtureBuilder::new(current_epoch)
+                .stake_accounts(&stake_accounts)
+                .build();
+            for s in stake_accounts {
+                let status = s.delegation().map_or_else(Default::default, |d| {
+                    d.stake_activating_and_deactivating(
+                        current_epoch,
+                        &history,
+                        NEW_RATE_ACTIVATION_EPOCH,
+                    )
+                });
+                assert_eq!(
+                    status,
+                    expected_status(s.lifecycle(), 1_000_000 * LAMPORTS_PER_SOL),
+                    "{:?} at epoch {current_epoch}",
+                    s.lifecycle()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn withdraw_only_from_inactive() {
+        const CURRENT_EPOCH: Epoch = 5;
+        let withdrawer = Keypair::new();
+        let stake_accounts: Vec<_> = ALL_LIFECYCLES
+            .iter()
+            .map(|l| {
+                (
+                    Pubkey::new_unique(),
+                    StakeAccountFixtureBuilder::new(*l, CURRENT_EPOCH)
+                        .authorized(SingleAuthorityAuthorized(withdrawer.pubkey())),
+                )
+            })
+            .collect();
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        ctx.warp_to_epoch(CURRENT_EPOCH).unwrap();
+        ctx.warp_forward_force_reward_interval_end().unwrap();
+        ctx.set_sysvar(
+            &StakeHistoryFixtureBuilder::new(CURRENT_EPOCH)
+                .stake_accounts(stake_accounts.iter().map(|(_, s)| s))
+                .build(),
+        );
+        for (addr, s) in stake_accounts.iter() {
+            ctx.set_account(addr, &s.into_account().into());
+        }
+
+        for (addr, s) in stake_accounts {
+            let total_lamports = s.build().total_lamports;
+            let mut tx = Transaction::new_with_payer(
+                &[withdraw(
+                    &addr,
+                    &withdrawer.pubkey(),
+                    &ctx.payer.pubkey(),
+                    total_lamports,
+                    None,
+                )],
+                Some(&ctx.payer.pubkey()),
+            );
+            let rbh = ctx.get_new_latest_blockhash().await.unwrap();
+            tx.sign(&[&ctx.payer, &withdrawer], rbh);
+            let res = ctx
+                .banks_client
+                .process_transaction(tx)
+                .await
+                .map_err(|e| e.unwrap());
+            match s.lifecycle() {
+                StakeLifecycle::Initialized | StakeLifecycle::Deactivated => {
+                    res.unwrap();
+                }
+                // the withdraw authority of an uninitialized stake account is itself
+                _ => assert!(
+                    matches!(res, Err(TransactionError::InstructionError(..))),
+                    "{:?}",
+                    s.lifecycle()
+                ),
+            }
+        }
+    }
+}
@@ -9,6 +9,10 @@ use solana_sdk::{account::Account, stake::stake_flags::StakeFlags};
 
 use crate::{est_rent_exempt_lamports, ExtendedProgramTest, IntoAccount};
 
+mod fixtures;
+
+pub use fixtures::*;
+
 #[cfg(feature = "proptest")]
 pub mod proptest_utils;
 
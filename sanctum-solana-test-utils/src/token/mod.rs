@@ -7,4 +7,5 @@ pub mod proptest_utils;
 #[cfg(feature = "token")]
 pub mod tokenkeg;
 
-// TODO: token-22
+#[cfg(feature = "token-2022")]
+pub mod token_2022;
@@ -1,10 +1,26 @@
 use proptest::{
+    option,
     prelude::prop_compose,
     strategy::{Just, Strategy, Union},
 };
-use spl_token_2022::state::{AccountState, Mint};
+use solana_program::pubkey::Pubkey;
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority,
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+        transfer_hook::{TransferHook, TransferHookAccount},
+    },
+    state::{AccountState, Mint},
+};
 
-use crate::proptest_utils::{coption_pubkey, coption_u64, pubkey};
+use crate::{
+    proptest_utils::{coption_pubkey, coption_u64, pubkey},
+    token::token_2022::{MockAccountExtension, MockMintExtension, Token22Account, Token22Mint},
+};
 
 pub fn token_account_state() -> impl Strategy<Value = AccountState> {
     Union::new([
@@ -57,3 +73,195 @@ prop_compose! {
             }
         }
 }
+
+/// `OptionalNonZeroPubkey` is not nameable without depending on `spl-pod`
+fn optional_nonzero_pubkey<T: TryFrom<Option<Pubkey>>>(pk: Option<Pubkey>) -> T
+where
+    T::Error: std::fmt::Debug,
+{
+    pk.filter(|pk| *pk != Pubkey::default()).try_into().unwrap()
+}
+
+prop_compose! {
+    pub fn transfer_fee()
+        (
+            epoch: u64,
+            maximum_fee: u64,
+            transfer_fee_basis_points in 0..=MAX_FEE_BASIS_POINTS,
+        ) -> TransferFee {
+            TransferFee {
+                epoch: epoch.into(),
+                maximum_fee: maximum_fee.into(),
+                transfer_fee_basis_points: transfer_fee_basis_points.into(),
+            }
+        }
+}
+
+prop_compose! {
+    pub fn transfer_fee_config()
+        (
+            transfer_fee_config_authority in option::of(pubkey()),
+            withdraw_withheld_authority in option::of(pubkey()),
+            withheld_amount: u64,
+            older_transfer_fee in transfer_fee(),
+            newer_transfer_fee in transfer_fee(),
+        ) -> TransferFeeConfig {
+            TransferFeeConfig {
+                transfer_fee_config_authority: optional_nonzero_pubkey(transfer_fee_config_authority),
+                withdraw_withheld_authority: optional_nonzero_pubkey(withdraw_withheld_authority),
+                withheld_amount: withheld_amount.into(),
+                older_transfer_fee,
+                newer_transfer_fee,
+            }
+        }
+}
+
+prop_compose! {
+    pub fn interest_bearing_config()
+        (
+            rate_authority in option::of(pubkey()),
+            initialization_timestamp: i64,
+            pre_update_average_rate: i16,
+            last_update_timestamp: i64,
+            current_rate: i16,
+        ) -> InterestBearingConfig {
+            InterestBearingConfig {
+                rate_authority: optional_nonzero_pubkey(rate_authority),
+                initialization_timestamp: initialization_timestamp.into(),
+                pre_update_average_rate: pre_update_average_rate.into(),
+                last_update_timestamp: last_update_timestamp.into(),
+                current_rate: current_rate.into(),
+            }
+        }
+}
+
+prop_compose! {
+    pub fn mint_close_authority()
+        (close_authority in option::of(pubkey())) -> MintCloseAuthority {
+            MintCloseAuthority { close_authority: optional_nonzero_pubkey(close_authority) }
+        }
+}
+
+prop_compose! {
+    pub fn default_account_state()
+        (state in token_account_state()) -> DefaultAccountState {
+            DefaultAccountState { state: state.into() }
+        }
+}
+
+prop_compose! {
+    pub fn permanent_delegate()
+        (delegate in option::of(pubkey())) -> PermanentDelegate {
+            PermanentDelegate { delegate: optional_nonzero_pubkey(delegate) }
+        }
+}
+
+prop_compose! {
+    pub fn transfer_hook()
+        (authority in option::of(pubkey()), program_id in option::of(pubkey())) -> TransferHook {
+            TransferHook {
+                authority: optional_nonzero_pubkey(authority),
+                program_id: optional_nonzero_pubkey(program_id),
+            }
+        }
+}
+
+prop_compose! {
+    pub fn metadata_pointer()
+        (authority in option::of(pubkey()), metadata_address in option::of(pubkey())) -> MetadataPointer {
+            MetadataPointer {
+                authority: optional_nonzero_pubkey(authority),
+                metadata_address: optional_nonzero_pubkey(metadata_address),
+            }
+        }
+}
+
+prop_compose! {
+    pub fn transfer_fee_amount()
+        (withheld_amount: u64) -> TransferFeeAmount {
+            TransferFeeAmount { withheld_amount: withheld_amount.into() }
+        }
+}
+
+prop_compose! {
+    pub fn transfer_hook_account()
+        (transferring: bool) -> TransferHookAccount {
+            TransferHookAccount { transferring: transferring.into() }
+        }
+}
+
+prop_compose! {
+    /// Any subset of the supported mint extensions, each appearing at most once
+    pub fn mint_extensions()
+        (
+            transfer_fee_config in option::of(transfer_fee_config()),
+            interest_bearing_config in option::of(interest_bearing_config()),
+            mint_close_authority in option::of(mint_close_authority()),
+            default_account_state in option::of(default_account_state()),
+            permanent_delegate in option::of(permanent_delegate()),
+            transfer_hook in option::of(transfer_hook()),
+            metadata_pointer in option::of(metadata_pointer()),
+        ) -> Vec<MockMintExtension> {
+            [
+                transfer_fee_config.map(MockMintExtension::TransferFeeConfig),
+                interest_bearing_config.map(MockMintExtension::InterestBearingConfig),
+                mint_close_authority.map(MockMintExtension::MintCloseAuthority),
+                default_account_state.map(MockMintExtension::DefaultAccountState),
+                permanent_delegate.map(MockMintExtension::PermanentDelegate),
+                transfer_hook.map(MockMintExtension::TransferHook),
+                metadata_pointer.map(MockMintExtension::MetadataPointer),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+}
+
+prop_compose! {
+    /// Any subset of the supported account extensions, each appearing at most once
+    pub fn account_extensions()
+        (
+            immutable_owner: bool,
+            transfer_fee_amount in option::of(transfer_fee_amount()),
+            transfer_hook_account in option::of(transfer_hook_account()),
+        ) -> Vec<MockAccountExtension> {
+            [
+                immutable_owner.then_some(MockAccountExtension::ImmutableOwner),
+                transfer_fee_amount.map(MockAccountExtension::TransferFeeAmount),
+                transfer_hook_account.map(MockAccountExtension::TransferHookAccount),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+}
+
+prop_compose! {
+    /// Always initialized
+    pub fn token22_mint_with_extensions()
+        (mint in token22_mint_no_extensions(), extensions in mint_extensions()) -> Token22Mint {
+            Token22Mint {
+                mint: Mint { is_initialized: true, ..mint },
+                extensions,
+            }
+        }
+}
+
+pub fn initialized_token_account_state() -> impl Strategy<Value = AccountState> {
+    Union::new([Just(AccountState::Initialized), Just(AccountState::Frozen)])
+}
+
+prop_compose! {
+    /// Always initialized
+    pub fn token22_account_with_extensions()
+        (
+            account in token22_account_no_extensions(),
+            state in initialized_token_account_state(),
+            extensions in account_extensions(),
+        ) -> Token22Account {
+            Token22Account {
+                account: spl_token_2022::state::Account { state, ..account },
+                extensions,
+            }
+        }
+}
@@ -0,0 +1,460 @@
+use solana_program::{program_option::COption, pubkey::Pubkey};
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::account::Account;
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState,
+        immutable_owner::ImmutableOwner,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority,
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        transfer_hook::{TransferHook, TransferHookAccount},
+        BaseStateWithExtensionsMut, Extension, ExtensionType, StateWithExtensionsMut,
+    },
+    state::{AccountState, Mint},
+};
+
+use crate::{est_rent_exempt_lamports, ExtendedProgramTest, IntoAccount};
+
+use super::{MockMintArgs, MockTokenAccountArgs};
+
+pub trait Token2022ProgramTest {
+    fn add_token22_account(self, addr: Pubkey, account: Token22Account) -> Self;
+    fn add_token22_account_from_args(self, addr: Pubkey, args: MockTokenAccountArgs) -> Self;
+    fn add_token22_mint_account(self, addr: Pubkey, mint: Token22Mint) -> Self;
+    fn add_token22_mint_from_args(self, addr: Pubkey, args: MockMintArgs) -> Self;
+}
+
+impl<T: ExtendedProgramTest> Token2022ProgramTest for T {
+    fn add_token22_account(self, addr: Pubkey, account: Token22Account) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: account.into_account(),
+        })
+    }
+
+    fn add_token22_account_from_args(self, addr: Pubkey, args: MockTokenAccountArgs) -> Self {
+        self.add_token22_account(addr, mock_token22_account(args, &[]))
+    }
+
+    fn add_token22_mint_account(self, addr: Pubkey, mint: Token22Mint) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: mint.into_account(),
+        })
+    }
+
+    fn add_token22_mint_from_args(self, addr: Pubkey, args: MockMintArgs) -> Self {
+        self.add_token22_mint_account(addr, mock_token22_mint(args, Vec::new()))
+    }
+}
+
+/// Token-2022 mint extensions supported by [`Token22Mint`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MockMintExtension {
+    TransferFeeConfig(TransferFeeConfig),
+    InterestBearingConfig(InterestBearingConfig),
+    MintCloseAuthority(MintCloseAuthority),
+    DefaultAccountState(DefaultAccountState),
+    PermanentDelegate(PermanentDelegate),
+    TransferHook(TransferHook),
+    MetadataPointer(MetadataPointer),
+}
+
+impl MockMintExtension {
+    /// A [`TransferFeeConfig`] with the same fee for all epochs and no authorities
+    pub fn transfer_fee(transfer_fee_basis_points: u16, maximum_fee: u64) -> Self {
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        };
+        Self::TransferFeeConfig(TransferFeeConfig {
+            older_transfer_fee: fee,
+            newer_transfer_fee: fee,
+            ..Default::default()
+        })
+    }
+
+    /// An [`InterestBearingConfig`] accruing at `rate_bps` since `initialization_timestamp`
+    /// with no rate authority
+    pub fn interest_bearing(rate_bps: i16, initialization_timestamp: i64) -> Self {
+        Self::InterestBearingConfig(InterestBearingConfig {
+            rate_authority: Default::default(),
+            initialization_timestamp: initialization_timestamp.into(),
+            pre_update_average_rate: rate_bps.into(),
+            last_update_timestamp: initialization_timestamp.into(),
+            current_rate: rate_bps.into(),
+        })
+    }
+
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            Self::TransferFeeConfig(_) => TransferFeeConfig::TYPE,
+            Self::InterestBearingConfig(_) => InterestBearingConfig::TYPE,
+            Self::MintCloseAuthority(_) => MintCloseAuthority::TYPE,
+            Self::DefaultAccountState(_) => DefaultAccountState::TYPE,
+            Self::PermanentDelegate(_) => PermanentDelegate::TYPE,
+            Self::TransferHook(_) => TransferHook::TYPE,
+            Self::MetadataPointer(_) => MetadataPointer::TYPE,
+        }
+    }
+}
+
+/// Token-2022 token account extensions supported by [`Token22Account`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MockAccountExtension {
+    ImmutableOwner,
+    TransferFeeAmount(TransferFeeAmount),
+    TransferHookAccount(TransferHookAccount),
+}
+
+impl MockAccountExtension {
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            Self::ImmutableOwner => ImmutableOwner::TYPE,
+            Self::TransferFeeAmount(_) => TransferFeeAmount::TYPE,
+            Self::TransferHookAccount(_) => TransferHookAccount::TYPE,
+        }
+    }
+}
+
+/// A Token-2022 mint with its TLV extensions.
+///
+/// Each extension type should only appear once
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token22Mint {
+    pub mint: Mint,
+    pub extensions: Vec<MockMintExtension>,
+}
+
+impl Token22Mint {
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        self.extensions.iter().map(|e| e.extension_type()).collect()
+    }
+
+    /// Account data length, same as `getAccountDataSize` for the mint's extensions
+    pub fn data_len(&self) -> usize {
+        ExtensionType::try_calculate_account_len::<Mint>(&self.extension_types()).unwrap()
+    }
+
+    /// The extensions that token accounts of this mint must have,
+    /// with their initial values
+    pub fn required_account_extensions(&self) -> Vec<MockAccountExtension> {
+        ExtensionType::get_required_init_account_extensions(&self.extension_types())
+            .into_iter()
+            .filter_map(|ty| match ty {
+                ExtensionType::ImmutableOwner => Some(MockAccountExtension::ImmutableOwner),
+                ExtensionType::TransferFeeAmount => {
+                    Some(MockAccountExtension::TransferFeeAmount(Default::default()))
+                }
+                ExtensionType::TransferHookAccount => {
+                    Some(MockAccountExtension::TransferHookAccount(Default::default()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The state new token accounts of this mint are initialized with
+    pub fn default_account_state(&self) -> AccountState {
+        self.extensions
+            .iter()
+            .find_map(|e| match e {
+                MockMintExtension::DefaultAccountState(DefaultAccountState { state }) => {
+                    AccountState::try_from(*state).ok()
+                }
+                _ => None,
+            })
+            .unwrap_or(AccountState::Initialized)
+    }
+}
+
+/// A Token-2022 token account with its TLV extensions.
+///
+/// Each extension type should only appear once
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token22Account {
+    pub account: spl_token_2022::state::Account,
+    pub extensions: Vec<MockAccountExtension>,
+}
+
+impl Token22Account {
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        self.extensions.iter().map(|e| e.extension_type()).collect()
+    }
+
+    /// Account data length, same as `getAccountDataSize` for the account's extensions
+    pub fn data_len(&self) -> usize {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+            &self.extension_types(),
+        )
+        .unwrap()
+    }
+}
+
+pub fn mock_token22_mint(
+    MockMintArgs {
+        mint_authority,
+        freeze_authority,
+        supply,
+        decimals,
+    }: MockMintArgs,
+    extensions: Vec<MockMintExtension>,
+) -> Token22Mint {
+    Token22Mint {
+        mint: Mint {
+            mint_authority: COption::from(mint_authority),
+            supply,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::from(freeze_authority),
+        },
+        extensions,
+    }
+}
+
+pub fn mock_token22_account(
+    MockTokenAccountArgs {
+        mint,
+        authority,
+        amount,
+    }: MockTokenAccountArgs,
+    extensions: &[MockAccountExtension],
+) -> Token22Account {
+    let mut res = Token22Account {
+        account: spl_token_2022::state::Account {
+            mint,
+            owner: authority,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        },
+        extensions: extensions.to_vec(),
+    };
+    if mint == spl_token_2022::native_mint::ID {
+        res.account.is_native = COption::Some(est_rent_exempt_lamports(res.data_len()));
+    }
+    res
+}
+
+/// A token account of `mint` with the account extensions and initial state it requires,
+/// like one created with `InitializeAccount`.
+///
+/// `args.mint` should be the address of `mint`
+pub fn mock_token22_account_for_mint(
+    args: MockTokenAccountArgs,
+    mint: &Token22Mint,
+) -> Token22Account {
+    let mut res = mock_token22_account(args, &mint.required_account_extensions());
+    res.account.state = mint.default_account_state();
+    res
+}
+
+impl IntoAccount for Token22Mint {
+    fn into_account(self) -> Account {
+        let mut data = vec![0u8; self.data_len()];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        for ext in self.extensions {
+            match ext {
+                MockMintExtension::TransferFeeConfig(e) => *state.init_extension(true).unwrap() = e,
+                MockMintExtension::InterestBearingConfig(e) => {
+                    *state.init_extension(true).unwrap() = e
+                }
+                MockMintExtension::MintCloseAuthority(e) => {
+                    *state.init_extension(true).unwrap() = e
+                }
+                MockMintExtension::DefaultAccountState(e) => {
+                    *state.init_extension(true).unwrap() = e
+                }
+                MockMintExtension::PermanentDelegate(e) => *state.init_extension(true).unwrap() = e,
+                MockMintExtension::TransferHook(e) => *state.init_extension(true).unwrap() = e,
+                MockMintExtension::MetadataPointer(e) => *state.init_extension(true).unwrap() = e,
+            }
+        }
+        state.base = self.mint;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        Account {
+            lamports: est_rent_exempt_lamports(data.len()),
+            data,
+            owner: spl_token_2022::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
+
+impl IntoAccount for Token22Account {
+    fn into_account(self) -> Account {
+        let mut data = vec![0u8; self.data_len()];
+        let mut lamports = est_rent_exempt_lamports(data.len());
+        if self.account.is_native.is_some() {
+            lamports += self.account.amount;
+        }
+        let mut state =
+            StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(
+                &mut data,
+            )
+            .unwrap();
+        for ext in self.extensions {
+            match ext {
+                MockAccountExtension::ImmutableOwner => {
+                    state.init_extension::<ImmutableOwner>(true).unwrap();
+                }
+                MockAccountExtension::TransferFeeAmount(e) => {
+                    *state.init_extension(true).unwrap() = e
+                }
+                MockAccountExtension::TransferHookAccount(e) => {
+                    *state.init_extension(true).unwrap() = e
+                }
+            }
+        }
+        state.base = self.account;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        Account {
+            lamports,
+            data,
+            owner: spl_token_2022::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+    use spl_token_2022::{
+        extension::transfer_fee::instruction::transfer_checked_with_fee,
+        extension::{BaseStateWithExtensions, StateWithExtensions},
+    };
+
+    use crate::ExtendedBanksClient;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn transfer_with_fee_on_chain() {
+        let mint_addr = Pubkey::new_unique();
+        let dst_addr = Pubkey::new_unique();
+        let src_addr = Pubkey::new_unique();
+        let owner = Keypair::new();
+        let mint = mock_token22_mint(
+            MockMintArgs {
+                mint_authority: None,
+                freeze_authority: None,
+                supply: 1_000_000,
+                decimals: 6,
+            },
+            vec![
+                MockMintExtension::transfer_fee(100, 5_000),
+                MockMintExtension::MintCloseAuthority(MintCloseAuthority::default()),
+            ],
+        );
+        let args = |authority| MockTokenAccountArgs {
+            mint: mint_addr,
+            authority,
+            amount: 0,
+        };
+        let mut src = mock_token22_account_for_mint(args(owner.pubkey()), &mint);
+        src.account.amount = 1_000_000;
+        let dst = mock_token22_account_for_mint(args(Pubkey::new_unique()), &mint);
+        let pt = ProgramTest::default()
+            .add_token22_mint_account(mint_addr, mint)
+            .add_token22_account(src_addr, src)
+            .add_token22_account(dst_addr, dst);
+        let (mut bc, payer, rbh) = pt.start().await;
+
+        let mut tx = Transaction::new_with_payer(
+            &[transfer_checked_with_fee(
+                &spl_token_2022::ID,
+                &src_addr,
+                &mint_addr,
+                &dst_addr,
+                &owner.pubkey(),
+                &[],
+                10_000,
+                6,
+                100,
+            )
+            .unwrap()],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer, &owner], rbh);
+        bc.process_transaction(tx).await.unwrap();
+
+        let dst = bc.get_account_unwrapped(dst_addr).await;
+        let dst = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&dst.data).unwrap();
+        assert_eq!(dst.base.amount, 9_900);
+        assert_eq!(
+            u64::from(
+                dst.get_extension::<TransferFeeAmount>()
+                    .unwrap()
+                    .withheld_amount
+            ),
+            100
+        );
+    }
+
+    #[cfg(feature = "proptest")]
+    mod proptests {
+        use proptest::prelude::*;
+
+        use crate::token::proptest_utils::token_2022::{
+            token22_account_with_extensions, token22_mint_with_extensions,
+        };
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn mint_round_trip(mint in token22_mint_with_extensions()) {
+                let account = mint.clone().into_account();
+                prop_assert_eq!(account.data.len(), mint.data_len());
+                let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+                prop_assert_eq!(state.base, mint.mint);
+                prop_assert_eq!(state.get_extension_types().unwrap(), mint.extension_types());
+                for ext in mint.extensions {
+                    let unpacked = match ext {
+                        MockMintExtension::TransferFeeConfig(_) => MockMintExtension::TransferFeeConfig(*state.get_extension().unwrap()),
+                        MockMintExtension::InterestBearingConfig(_) => MockMintExtension::InterestBearingConfig(*state.get_extension().unwrap()),
+                        MockMintExtension::MintCloseAuthority(_) => MockMintExtension::MintCloseAuthority(*state.get_extension().unwrap()),
+                        MockMintExtension::DefaultAccountState(_) => MockMintExtension::DefaultAccountState(*state.get_extension().unwrap()),
+                        MockMintExtension::PermanentDelegate(_) => MockMintExtension::PermanentDelegate(*state.get_extension().unwrap()),
+                        MockMintExtension::TransferHook(_) => MockMintExtension::TransferHook(*state.get_extension().unwrap()),
+                        MockMintExtension::MetadataPointer(_) => MockMintExtension::MetadataPointer(*state.get_extension().unwrap()),
+                    };
+                    prop_assert_eq!(unpacked, ext);
+                }
+            }
+
+            #[test]
+            fn account_round_trip(account in token22_account_with_extensions()) {
+                let acc = account.clone().into_account();
+                prop_assert_eq!(acc.data.len(), account.data_len());
+                let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&acc.data).unwrap();
+                prop_assert_eq!(state.base, account.account);
+                prop_assert_eq!(state.get_extension_types().unwrap(), account.extension_types());
+                for ext in account.extensions {
+                    let unpacked = match ext {
+                        MockAccountExtension::ImmutableOwner => {
+                            state.get_extension::<ImmutableOwner>().unwrap();
+                            MockAccountExtension::ImmutableOwner
+                        }
+                        MockAccountExtension::TransferFeeAmount(_) => MockAccountExtension::TransferFeeAmount(*state.get_extension().unwrap()),
+                        MockAccountExtension::TransferHookAccount(_) => MockAccountExtension::TransferHookAccount(*state.get_extension().unwrap()),
+                    };
+                    prop_assert_eq!(unpacked, ext);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use std::{error::Error, fmt::Display};
+
+use async_trait::async_trait;
+use borsh::de::BorshDeserialize;
+use data_encoding::BASE64;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::{BanksClient, BanksClientError, BanksTransactionResultWithMetadata};
+use solana_sdk::{
+    account::Account,
+    transaction::{TransactionError, VersionedTransaction},
+    transaction_context::TransactionReturnData,
+};
+
+#[derive(Debug)]
+pub enum TryExtendedBanksClientError {
+    /// Error communicating with the bank or the transaction failed sanitization
+    Banks(BanksClientError),
+
+    AccountNotFound(Pubkey),
+
+    AccountExists(Pubkey),
+
+    /// Account data failed to deserialize
+    Deserialize {
+        addr: Pubkey,
+        source: std::io::Error,
+    },
+
+    /// The transaction was executed but failed
+    Transaction {
+        err: TransactionError,
+        compute_units_consumed: u64,
+        log_messages: Vec<String>,
+    },
+
+    /// The transaction succeeded but did not set any return data
+    NoReturnData {
+        compute_units_consumed: u64,
+        log_messages: Vec<String>,
+    },
+
+    Base64(data_encoding::DecodeError),
+
+    Bincode(bincode::Error),
+}
+
+impl Display for TryExtendedBanksClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Banks(e) => write!(f, "BanksClientError: {e}"),
+            Self::AccountNotFound(addr) => write!(f, "Account {addr} not found"),
+            Self::AccountExists(addr) => write!(f, "Account {addr} exists"),
+            Self::Deserialize { addr, source } => {
+                write!(f, "Failed to deserialize account {addr}: {source}")
+            }
+            Self::Transaction {
+                err,
+                compute_units_consumed,
+                log_messages,
+            } => {
+                write!(
+                    f,
+                    "Transaction failed: {err}. CUs consumed: {compute_units_consumed}. Logs:"
+                )?;
+                log_messages.iter().try_for_each(|l| write!(f, "\n{l}"))
+            }
+            Self::NoReturnData {
+                compute_units_consumed,
+                log_messages,
+            } => {
+                write!(
+                    f,
+                    "Transaction set no return data. CUs consumed: {compute_units_consumed}. Logs:"
+                )?;
+                log_messages.iter().try_for_each(|l| write!(f, "\n{l}"))
+            }
+            Self::Base64(e) => write!(f, "Invalid base64: {e}"),
+            Self::Bincode(e) => write!(f, "Invalid bincode: {e}"),
+        }
+    }
+}
+
+impl Error for TryExtendedBanksClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Banks(e) => Some(e),
+            Self::Deserialize { source, .. } => Some(source),
+            Self::Transaction { err, .. } => Some(err),
+            Self::Base64(e) => Some(e),
+            Self::Bincode(e) => Some(e),
+            Self::AccountNotFound(_) | Self::AccountExists(_) | Self::NoReturnData { .. } => None,
+        }
+    }
+}
+
+impl From<BanksClientError> for TryExtendedBanksClientError {
+    fn from(e: BanksClientError) -> Self {
+        Self::Banks(e)
+    }
+}
+
+impl From<data_encoding::DecodeError> for TryExtendedBanksClientError {
+    fn from(e: data_encoding::DecodeError) -> Self {
+        Self::Base64(e)
+    }
+}
+
+impl From<bincode::Error> for TryExtendedBanksClientError {
+    fn from(e: bincode::Error) -> Self {
+        Self::Bincode(e)
+    }
+}
+
+/// The output of a successfully executed transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecOutput<T> {
+    pub value: T,
+    pub compute_units_consumed: u64,
+    pub log_messages: Vec<String>,
+}
+
+/// Non-panicking version of [`crate::ExtendedBanksClient`]
+#[async_trait]
+pub trait TryExtendedBanksClient {
+    async fn try_exec<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> Result<ExecOutput<()>, TryExtendedBanksClientError>;
+
+    /// NB: return data is truncated. Probably wanna pass it through zero_padded_return_data() first
+    async fn try_exec_get_return_data<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> Result<ExecOutput<TransactionReturnData>, TryExtendedBanksClientError>;
+
+    async fn try_get_account(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<Account, TryExtendedBanksClientError>;
+
+    async fn try_get_account_data(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<Vec<u8>, TryExtendedBanksClientError>;
+
+    async fn try_get_borsh_account<T: BorshDeserialize>(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<T, TryExtendedBanksClientError>;
+
+    async fn try_assert_account_not_exist(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<(), TryExtendedBanksClientError>;
+
+    /// Execute a base64-encoded legacy or versioned transaction
+    ///
+    /// Args:
+    /// - `b64_tx` the base64 string, NOT the decoded bytes. If `str` or `String`, use `str.as_bytes()`
+    async fn try_exec_b64_tx(
+        &mut self,
+        b64_tx: &[u8],
+    ) -> Result<ExecOutput<()>, TryExtendedBanksClientError>;
+}
+
+#[async_trait]
+impl<B: TryExtendedBanksClient + Send> TryExtendedBanksClient for &mut B {
+    async fn try_exec<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> Result<ExecOutput<()>, TryExtendedBanksClientError> {
+        (*self).try_exec(tx).await
+    }
+
+    async fn try_exec_get_return_data<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> Result<ExecOutput<TransactionReturnData>, TryExtendedBanksClientError> {
+        (*self).try_exec_get_return_data(tx).await
+    }
+
+    async fn try_get_account(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<Account, TryExtendedBanksClientError> {
+        (*self).try_get_account(addr).await
+    }
+
+    async fn try_get_account_data(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<Vec<u8>, TryExtendedBanksClientError> {
+        (*self).try_get_account_data(addr).await
+    }
+
+    async fn try_get_borsh_account<T: BorshDeserialize>(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<T, TryExtendedBanksClientError> {
+        (*self).try_get_borsh_account(addr).await
+    }
+
+    async fn try_assert_account_not_exist(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<(), TryExtendedBanksClientError> {
+        (*self).try_assert_account_not_exist(addr).await
+    }
+
+    async fn try_exec_b64_tx(
+        &mut self,
+        b64_tx: &[u8],
+    ) -> Result<ExecOutput<()>, TryExtendedBanksClientError> {
+        (*self).try_exec_b64_tx(b64_tx).await
+    }
+}
+
+/// Returns the tx's return data along with the output
+async fn exec_with_metadata(
+    bc: &mut BanksClient,
+    tx: VersionedTransaction,
+) -> Result<ExecOutput<Option<TransactionReturnData>>, TryExtendedBanksClientError> {
+    let BanksTransactionResultWithMetadata { result, metadata } =
+        bc.process_transaction_with_metadata(tx).await?;
+    let (compute_units_consumed, log_messages, return_data) = metadata
+        .map(|m| (m.compute_units_consumed, m.log_messages, m.return_data))
+        .unwrap_or_default();
+    match result {
+        Ok(()) => Ok(ExecOutput {
+            value: return_data,
+            compute_units_consumed,
+            log_messages,
+        }),
+        Err(err) => Err(TryExtendedBanksClientError::Transaction {
+            err,
+            compute_units_consumed,
+            log_messages,
+        }),
+    }
+}
+
+#[async_trait]
+impl TryExtendedBanksClient for BanksClient {
+    async fn try_exec<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> Result<ExecOutput<()>, TryExtendedBanksClientError> {
+        let ExecOutput {
+            compute_units_consumed,
+            log_messages,
+            ..
+        } = exec_with_metadata(self, tx.into()).await?;
+        Ok(ExecOutput {
+            value: (),
+            compute_units_consumed,
+            log_messages,
+        })
+    }
+
+    async fn try_exec_get_return_data<T: Into<VersionedTransaction> + Send>(
+        &mut self,
+        tx: T,
+    ) -> Result<ExecOutput<TransactionReturnData>, TryExtendedBanksClientError> {
+        let ExecOutput {
+            value,
+            compute_units_consumed,
+            log_messages,
+        } = exec_with_metadata(self, tx.into()).await?;
+        match value {
+            Some(value) => Ok(ExecOutput {
+                value,
+                compute_units_consumed,
+                log_messages,
+            }),
+            None => Err(TryExtendedBanksClientError::NoReturnData {
+                compute_units_consumed,
+                log_messages,
+            }),
+        }
+    }
+
+    async fn try_get_account(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<Account, TryExtendedBanksClientError> {
+        self.get_account(addr)
+            .await?
+            .ok_or(TryExtendedBanksClientError::AccountNotFound(addr))
+    }
+
+    async fn try_get_account_data(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<Vec<u8>, TryExtendedBanksClientError> {
+        Ok(self.try_get_account(addr).await?.data)
+    }
+
+    async fn try_get_borsh_account<T: BorshDeserialize>(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<T, TryExtendedBanksClientError> {
+        let data = self.try_get_account_data(addr).await?;
+        T::deserialize(&mut data.as_ref())
+            .map_err(|source| TryExtendedBanksClientError::Deserialize { addr, source })
+    }
+
+    async fn try_assert_account_not_exist(
+        &mut self,
+        addr: Pubkey,
+    ) -> Result<(), TryExtendedBanksClientError> {
+        match self.get_account(addr).await? {
+            None => Ok(()),
+            Some(_) => Err(TryExtendedBanksClientError::AccountExists(addr)),
+        }
+    }
+
+    async fn try_exec_b64_tx(
+        &mut self,
+        b64_tx: &[u8],
+    ) -> Result<ExecOutput<()>, TryExtendedBanksClientError> {
+        let bytes = BASE64.decode(b64_tx)?;
+        let tx: VersionedTransaction = bincode::deserialize(&bytes)?;
+        self.try_exec(tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::system_instruction;
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{signer::Signer, transaction::Transaction};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn try_errors_instead_of_panics() {
+        let pt = ProgramTest::default();
+        let (mut banks_client, payer, rbh) = pt.start().await;
+        let missing = Pubkey::new_unique();
+
+        assert!(matches!(
+            banks_client.try_get_account(missing).await,
+            Err(TryExtendedBanksClientError::AccountNotFound(addr)) if addr == missing
+        ));
+        banks_client
+            .try_assert_account_not_exist(missing)
+            .await
+            .unwrap();
+        assert!(matches!(
+            banks_client
+                .try_get_borsh_account::<[u8; 1024]>(payer.pubkey())
+                .await,
+            Err(TryExtendedBanksClientError::Deserialize { .. })
+        ));
+        assert!(matches!(
+            banks_client.try_exec_b64_tx(b"not base64!").await,
+            Err(TryExtendedBanksClientError::Base64(_))
+        ));
+        assert!(matches!(
+            banks_client
+                .try_exec_b64_tx(BASE64.encode(&[1, 2, 3]).as_bytes())
+                .await,
+            Err(TryExtendedBanksClientError::Bincode(_))
+        ));
+
+        let mut tx = Transaction::new_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &missing,
+                u64::MAX,
+            )],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], rbh);
+        match banks_client.try_exec(tx).await {
+            Err(TryExtendedBanksClientError::Transaction {
+                compute_units_consumed,
+                log_messages,
+                ..
+            }) => {
+                assert!(compute_units_consumed > 0);
+                assert!(!log_messages.is_empty());
+            }
+            r => panic!("unexpected {r:?}"),
+        }
+
+        let mut tx = Transaction::new_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &missing,
+                1_000_000,
+            )],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], rbh);
+        let out = banks_client.try_exec(tx.clone()).await.unwrap();
+        assert!(out.compute_units_consumed > 0);
+        assert!(!out.log_messages.is_empty());
+
+        assert!(matches!(
+            banks_client.try_assert_account_not_exist(missing).await,
+            Err(TryExtendedBanksClientError::AccountExists(_))
+        ));
+    }
+}
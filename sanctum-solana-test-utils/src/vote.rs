@@ -0,0 +1,345 @@
+use solana_program::{
+    clock::{Clock, Epoch},
+    hash::hashv,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    vote::{
+        self,
+        state::{VoteInit, VoteState, VoteStateVersions, MAX_EPOCH_CREDITS_HISTORY},
+    },
+};
+use solana_readonly_account::keyed::Keyed;
+use solana_sdk::account::Account;
+
+use crate::{est_rent_exempt_lamports, ExtendedProgramTest, IntoAccount};
+
+/// Roughly what a well-performing mainnet-beta validator earns in an epoch
+pub const DEFAULT_CREDITS_PER_EPOCH: u64 = 400_000;
+
+/// Lamports given to the node identity of each validator added by
+/// [`VoteProgramTest::add_mock_validators`] to pay for votes
+pub const MOCK_VALIDATOR_IDENTITY_LAMPORTS: u64 = LAMPORTS_PER_SOL;
+
+/// The `epoch_credits` history of a vote account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VoteCredits {
+    /// Earned `credits_per_epoch` credits in every epoch of the history
+    /// up to and including the current epoch.
+    ///
+    /// Acceptable as the reference vote account of `DeactivateDelinquent`
+    /// from epoch 4 onwards
+    Healthy { credits_per_epoch: u64 },
+
+    /// Earned `credits_per_epoch` credits in every epoch of the history
+    /// up to and including `last_voted_epoch`, or never voted if `None`.
+    ///
+    /// Stake delegated to it can be deactivated with `DeactivateDelinquent`
+    /// if `last_voted_epoch` is at least 5 epochs before the current epoch
+    Delinquent {
+        last_voted_epoch: Option<Epoch>,
+        credits_per_epoch: u64,
+    },
+
+    /// `(epoch, credits, prev_credits)`, oldest first
+    Custom(Vec<(Epoch, u64, u64)>),
+}
+
+impl VoteCredits {
+    pub const fn healthy() -> Self {
+        Self::Healthy {
+            credits_per_epoch: DEFAULT_CREDITS_PER_EPOCH,
+        }
+    }
+
+    pub const fn delinquent(last_voted_epoch: Option<Epoch>) -> Self {
+        Self::Delinquent {
+            last_voted_epoch,
+            credits_per_epoch: DEFAULT_CREDITS_PER_EPOCH,
+        }
+    }
+
+    /// The `epoch_credits` of the vote account as of `current_epoch`
+    pub fn epoch_credits(&self, current_epoch: Epoch) -> Vec<(Epoch, u64, u64)> {
+        let (last_voted_epoch, credits_per_epoch) = match self {
+            Self::Custom(v) => return v.clone(),
+            Self::Healthy { credits_per_epoch } => (current_epoch, *credits_per_epoch),
+            Self::Delinquent {
+                last_voted_epoch: None,
+                ..
+            } => return Vec::new(),
+            Self::Delinquent {
+                last_voted_epoch: Some(e),
+                credits_per_epoch,
+            } => (*e, *credits_per_epoch),
+        };
+        let first_epoch = last_voted_epoch.saturating_sub(MAX_EPOCH_CREDITS_HISTORY as u64 - 1);
+        (first_epoch..=last_voted_epoch)
+            .map(|epoch| {
+                let prev_credits = (epoch - first_epoch) * credits_per_epoch;
+                (epoch, prev_credits + credits_per_epoch, prev_credits)
+            })
+            .collect()
+    }
+}
+
+/// Builds a vote account as of `current_epoch`.
+///
+/// Defaults to a healthy validator with 0% commission
+/// whose authorized voter and withdrawer are the node identity
+#[derive(Clone, Debug)]
+pub struct VoteAccountFixtureBuilder {
+    node_pubkey: Pubkey,
+    authorized_voter: Pubkey,
+    authorized_withdrawer: Pubkey,
+    commission: u8,
+    credits: VoteCredits,
+    current_epoch: Epoch,
+}
+
+impl VoteAccountFixtureBuilder {
+    pub fn new(node_pubkey: Pubkey, current_epoch: Epoch) -> Self {
+        Self {
+            node_pubkey,
+            authorized_voter: node_pubkey,
+            authorized_withdrawer: node_pubkey,
+            commission: 0,
+            credits: VoteCredits::healthy(),
+            current_epoch,
+        }
+    }
+
+    pub fn authorized_voter(mut self, authorized_voter: Pubkey) -> Self {
+        self.authorized_voter = authorized_voter;
+        self
+    }
+
+    pub fn authorized_withdrawer(mut self, authorized_withdrawer: Pubkey) -> Self {
+        self.authorized_withdrawer = authorized_withdrawer;
+        self
+    }
+
+    /// Percentage
+    pub fn commission(mut self, commission: u8) -> Self {
+        self.commission = commission;
+        self
+    }
+
+    pub fn credits(mut self, credits: VoteCredits) -> Self {
+        self.credits = credits;
+        self
+    }
+
+    pub fn node_pubkey(&self) -> Pubkey {
+        self.node_pubkey
+    }
+
+    pub fn build(&self) -> VoteState {
+        let mut vote_state = VoteState::new(
+            &VoteInit {
+                node_pubkey: self.node_pubkey,
+                authorized_voter: self.authorized_voter,
+                authorized_withdrawer: self.authorized_withdrawer,
+                commission: self.commission,
+            },
+            &Clock {
+                epoch: self.current_epoch,
+                leader_schedule_epoch: self.current_epoch + 1,
+                ..Default::default()
+            },
+        );
+        vote_state.epoch_credits = self.credits.epoch_credits(self.current_epoch);
+        vote_state
+    }
+}
+
+impl IntoAccount for VoteAccountFixtureBuilder {
+    fn into_account(self) -> Account {
+        let mut data = vec![0u8; VoteState::size_of()];
+        VoteState::serialize(&VoteStateVersions::new_current(self.build()), &mut data).unwrap();
+        Account {
+            lamports: est_rent_exempt_lamports(data.len()),
+            data,
+            owner: vote::program::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MockValidator {
+    pub vote: Pubkey,
+    pub vote_account: VoteAccountFixtureBuilder,
+}
+
+impl MockValidator {
+    pub fn node_pubkey(&self) -> Pubkey {
+        self.vote_account.node_pubkey()
+    }
+}
+
+fn mock_validator_pubkey(i: usize, name: &str) -> Pubkey {
+    Pubkey::new_from_array(
+        hashv(&[b"mock-validator", &i.to_le_bytes(), name.as_bytes()]).to_bytes(),
+    )
+}
+
+/// `n` healthy validators with addresses that are the same across calls
+pub fn mock_validators(n: usize, current_epoch: Epoch) -> Vec<MockValidator> {
+    (0..n)
+        .map(|i| MockValidator {
+            vote: mock_validator_pubkey(i, "vote"),
+            vote_account: VoteAccountFixtureBuilder::new(
+                mock_validator_pubkey(i, "node"),
+                current_epoch,
+            ),
+        })
+        .collect()
+}
+
+pub trait VoteProgramTest {
+    fn add_vote_account(self, addr: Pubkey, vote_account: VoteAccountFixtureBuilder) -> Self;
+
+    /// Adds the vote accounts and funded node identities of the validators
+    fn add_mock_validators(self, validators: &[MockValidator]) -> Self;
+}
+
+impl<T: ExtendedProgramTest> VoteProgramTest for T {
+    fn add_vote_account(self, addr: Pubkey, vote_account: VoteAccountFixtureBuilder) -> Self {
+        self.add_keyed_account(Keyed {
+            pubkey: addr,
+            account: vote_account.into_account(),
+        })
+    }
+
+    fn add_mock_validators(self, validators: &[MockValidator]) -> Self {
+        validators.iter().fold(self, |pt, v| {
+            pt.add_vote_account(v.vote, v.vote_account.clone())
+                .add_system_account(v.node_pubkey(), MOCK_VALIDATOR_IDENTITY_LAMPORTS)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "vote"))]
+mod tests {
+    use solana_program::stake;
+
+    use super::*;
+
+    #[test]
+    fn credits_history() {
+        assert!(stake::tools::acceptable_reference_epoch_credits(
+            &VoteCredits::healthy().epoch_credits(100),
+            100
+        ));
+        let credits = VoteCredits::delinquent(Some(95)).epoch_credits(100);
+        assert_eq!(credits.len(), MAX_EPOCH_CREDITS_HISTORY);
+        assert_eq!(credits.last().unwrap().0, 95);
+        assert!(stake::tools::eligible_for_deactivate_delinquent(
+            &credits, 100
+        ));
+        assert!(!stake::tools::eligible_for_deactivate_delinquent(
+            &VoteCredits::delinquent(Some(96)).epoch_credits(100),
+            100
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "vote", feature = "stake"))]
+mod stake_tests {
+    use solana_program::stake::{
+        instruction::{deactivate_delinquent_stake, delegate_stake},
+        state::StakeStateV2,
+    };
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+
+    use crate::{
+        stake::{SingleAuthorityAuthorized, StakeAccountFixtureBuilder, StakeLifecycle},
+        ExtendedBanksClient,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn delegate_to_mock_validator() {
+        let validators = mock_validators(3, 0);
+        let staker = Keypair::new();
+        let stake_addr = Pubkey::new_unique();
+        let (mut bc, payer, rbh) = ProgramTest::default()
+            .add_mock_validators(&validators)
+            .add_account_chained(
+                stake_addr,
+                StakeAccountFixtureBuilder::new(StakeLifecycle::Initialized, 0)
+                    .authorized(SingleAuthorityAuthorized(staker.pubkey()))
+                    .into_account(),
+            )
+            .start()
+            .await;
+
+        let mut tx = Transaction::new_with_payer(
+            &[delegate_stake(
+                &stake_addr,
+                &staker.pubkey(),
+                &validators[2].vote,
+            )],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer, &staker], rbh);
+        bc.process_transaction(tx).await.unwrap();
+
+        let stake: StakeStateV2 =
+            bincode::deserialize(&bc.get_account_data(stake_addr).await).unwrap();
+        assert_eq!(stake.delegation().unwrap().voter_pubkey, validators[2].vote);
+    }
+
+    #[tokio::test]
+    async fn deactivate_delinquent() {
+        const CURRENT_EPOCH: Epoch = 10;
+        let delinquent_vote = Pubkey::new_unique();
+        let reference_vote = Pubkey::new_unique();
+        let stake_addr = Pubkey::new_unique();
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        ctx.warp_to_epoch(CURRENT_EPOCH).unwrap();
+        ctx.warp_forward_force_reward_interval_end().unwrap();
+        ctx.set_account(
+            &delinquent_vote,
+            &VoteAccountFixtureBuilder::new(Pubkey::new_unique(), CURRENT_EPOCH)
+                .credits(VoteCredits::delinquent(Some(CURRENT_EPOCH - 5)))
+                .commission(100)
+                .into_account()
+                .into(),
+        );
+        ctx.set_account(
+            &reference_vote,
+            &VoteAccountFixtureBuilder::new(Pubkey::new_unique(), CURRENT_EPOCH)
+                .into_account()
+                .into(),
+        );
+        ctx.set_account(
+            &stake_addr,
+            &StakeAccountFixtureBuilder::new(StakeLifecycle::Active, CURRENT_EPOCH)
+                .voter(delinquent_vote)
+                .into_account()
+                .into(),
+        );
+
+        let mut tx = Transaction::new_with_payer(
+            &[deactivate_delinquent_stake(
+                &stake_addr,
+                &delinquent_vote,
+                &reference_vote,
+            )],
+            Some(&ctx.payer.pubkey()),
+        );
+        tx.sign(&[&ctx.payer], ctx.last_blockhash);
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+
+        let stake: StakeStateV2 =
+            bincode::deserialize(&ctx.banks_client.get_account_data(stake_addr).await).unwrap();
+        assert_eq!(
+            stake.delegation().unwrap().deactivation_epoch,
+            CURRENT_EPOCH
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt::{Debug, Display};
+
+use solana_program::{hash::hashv, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::{keypair::keypair_from_seed, Signer, SignerError},
+};
+
+use crate::{ExtendedProgramTest, ASSOCIATED_TOKEN_PROGRAM_ID};
+
+#[cfg(any(feature = "token", feature = "token-2022"))]
+use crate::token::MockTokenAccountArgs;
+
+#[cfg(feature = "token")]
+use crate::token::tokenkeg::TokenkegProgramTest;
+
+#[cfg(feature = "token-2022")]
+use crate::token::token_2022::{
+    mock_token22_account_for_mint, MockAccountExtension, Token2022ProgramTest, Token22Account,
+    Token22Mint,
+};
+
+/// Lamports each wallet created by a [`TestWalletFactory`] is funded with by default
+pub const DEFAULT_TEST_WALLET_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+
+/// The `index`-th keypair of the test `test_name`. Same across runs
+pub fn test_keypair(test_name: &str, index: usize) -> Keypair {
+    let seed = hashv(&[b"test-wallet", test_name.as_bytes(), &index.to_le_bytes()]);
+    keypair_from_seed(seed.as_ref()).unwrap()
+}
+
+/// The associated token account of `wallet` for `mint`
+pub fn find_ata_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WalletTokenAccount {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+
+    /// The wallet's ATA for `mint`
+    pub addr: Pubkey,
+}
+
+/// A funded keypair created by [`TestWalletFactory`].
+///
+/// Displays as `<name> (<pubkey>)` so that failing tests print recognisable addresses
+pub struct TestWallet {
+    pub name: String,
+    pub keypair: Keypair,
+    pub token_accounts: Vec<WalletTokenAccount>,
+}
+
+impl TestWallet {
+    /// The address of this wallet's token account for `mint`
+    ///
+    /// ## Panics
+    /// If the wallet was not created with a token account for `mint`
+    pub fn ata(&self, mint: &Pubkey) -> Pubkey {
+        self.token_accounts
+            .iter()
+            .find(|t| t.mint == *mint)
+            .unwrap_or_else(|| panic!("{self} has no token account for mint {mint}"))
+            .addr
+    }
+}
+
+impl Display for TestWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.keypair.pubkey())
+    }
+}
+
+impl Debug for TestWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestWallet")
+            .field("name", &self.name)
+            .field("pubkey", &self.keypair.pubkey())
+            .field("token_accounts", &self.token_accounts)
+            .finish()
+    }
+}
+
+impl Signer for TestWallet {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        self.keypair.try_pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self.keypair.try_sign_message(message)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.keypair.is_interactive()
+    }
+}
+
+#[cfg(any(feature = "token", feature = "token-2022"))]
+#[derive(Clone, Debug)]
+enum TokenAccountTemplate {
+    #[cfg(feature = "token")]
+    Tokenkeg { mint: Pubkey, amount: u64 },
+
+    /// `account.owner` is replaced with the wallet's pubkey
+    #[cfg(feature = "token-2022")]
+    Token22 { account: Token22Account },
+}
+
+/// Creates funded [`TestWallet`]s with keypairs derived from the test name
+/// and the order of creation.
+///
+/// ## Example
+///
+/// ```ignore
+/// let mut wallets = TestWalletFactory::new("deposit_basic").with_tokenkeg_account(mint, 1_000);
+/// let (pt, alice) = wallets.create(pt, "alice");
+/// let (pt, bob) = wallets.create(pt, "bob");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestWalletFactory {
+    test_name: String,
+    next_index: usize,
+    lamports: u64,
+
+    #[cfg(any(feature = "token", feature = "token-2022"))]
+    token_accounts: Vec<TokenAccountTemplate>,
+}
+
+impl TestWalletFactory {
+    pub fn new(test_name: &str) -> Self {
+        Self {
+            test_name: test_name.to_owned(),
+            next_index: 0,
+            lamports: DEFAULT_TEST_WALLET_LAMPORTS,
+            #[cfg(any(feature = "token", feature = "token-2022"))]
+            token_accounts: Vec::new(),
+        }
+    }
+
+    /// Fund subsequently created wallets with `lamports` instead of
+    /// [`DEFAULT_TEST_WALLET_LAMPORTS`]
+    pub fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// Give subsequently created wallets a tokenkeg ATA of `mint` with `amount` tokens
+    #[cfg(feature = "token")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "token")))]
+    pub fn with_tokenkeg_account(mut self, mint: Pubkey, amount: u64) -> Self {
+        self.token_accounts
+            .push(TokenAccountTemplate::Tokenkeg { mint, amount });
+        self
+    }
+
+    /// Give subsequently created wallets a Token-2022 ATA of `mint` with `amount` tokens,
+    /// with the account extensions and initial state `mint_account` requires
+    /// and `ImmutableOwner`, like one created by the associated token program
+    #[cfg(feature = "token-2022")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "token-2022")))]
+    pub fn with_token22_account(
+        mut self,
+        mint: Pubkey,
+        mint_account: &Token22Mint,
+        amount: u64,
+    ) -> Self {
+        let mut account = mock_token22_account_for_mint(
+            MockTokenAccountArgs {
+                mint,
+                authority: Pubkey::default(),
+                amount,
+            },
+            mint_account,
+        );
+        if !account
+            .extensions
+            .contains(&MockAccountExtension::ImmutableOwner)
+        {
+            account
+                .extensions
+                .push(MockAccountExtension::ImmutableOwner);
+        }
+        self.token_accounts
+            .push(TokenAccountTemplate::Token22 { account });
+        self
+    }
+
+    pub fn test_name(&self) -> &str {
+        &self.test_name
+    }
+
+    /// Adds the next wallet, named `name`, and its token accounts to `pt`
+    pub fn create<T: ExtendedProgramTest>(&mut self, pt: T, name: &str) -> (T, TestWallet) {
+        let keypair = test_keypair(&self.test_name, self.next_index);
+        self.next_index += 1;
+        let wallet = keypair.pubkey();
+        let pt = pt.add_system_account(wallet, self.lamports);
+        #[cfg(any(feature = "token", feature = "token-2022"))]
+        let (pt, token_accounts) = self.token_accounts.iter().fold(
+            (pt, Vec::new()),
+            |(pt, mut token_accounts), template| {
+                let (pt, token_account) = add_token_account(pt, wallet, template.clone());
+                token_accounts.push(token_account);
+                (pt, token_accounts)
+            },
+        );
+        #[cfg(not(any(feature = "token", feature = "token-2022")))]
+        let token_accounts = Vec::new();
+        (
+            pt,
+            TestWallet {
+                name: name.to_owned(),
+                keypair,
+                token_accounts,
+            },
+        )
+    }
+
+    /// [`Self::create`] for each of `names`, in order
+    pub fn create_all<T: ExtendedProgramTest>(
+        &mut self,
+        pt: T,
+        names: &[&str],
+    ) -> (T, Vec<TestWallet>) {
+        names
+            .iter()
+            .fold((pt, Vec::new()), |(pt, mut wallets), name| {
+                let (pt, wallet) = self.create(pt, name);
+                wallets.push(wallet);
+                (pt, wallets)
+            })
+    }
+}
+
+#[cfg(any(feature = "token", feature = "token-2022"))]
+fn add_token_account<T: ExtendedProgramTest>(
+    pt: T,
+    wallet: Pubkey,
+    template: TokenAccountTemplate,
+) -> (T, WalletTokenAccount) {
+    match template {
+        #[cfg(feature = "token")]
+        TokenAccountTemplate::Tokenkeg { mint, amount } => {
+            let addr = find_ata_address(&wallet, &mint, &spl_token::ID);
+            let pt = pt.add_tokenkeg_account_from_args(
+                addr,
+                MockTokenAccountArgs {
+                    mint,
+                    authority: wallet,
+                    amount,
+                },
+            );
+            (
+                pt,
+                WalletTokenAccount {
+                    mint,
+                    token_program: spl_token::ID,
+                    addr,
+                },
+            )
+        }
+        #[cfg(feature = "token-2022")]
+        TokenAccountTemplate::Token22 { mut account } => {
+            let mint = account.account.mint;
+            account.account.owner = wallet;
+            let addr = find_ata_address(&wallet, &mint, &spl_token_2022::ID);
+            let pt = pt.add_token22_account(addr, account);
+            (
+                pt,
+                WalletTokenAccount {
+                    mint,
+                    token_program: spl_token_2022::ID,
+                    addr,
+                },
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program_test::ProgramTest;
+
+    use crate::ExtendedBanksClient;
+
+    use super::*;
+
+    #[test]
+    fn keypairs_deterministic() {
+        assert_eq!(
+            test_keypair("a", 0).to_bytes(),
+            test_keypair("a", 0).to_bytes()
+        );
+        assert_ne!(test_keypair("a", 0).pubkey(), test_keypair("a", 1).pubkey());
+        assert_ne!(test_keypair("a", 0).pubkey(), test_keypair("b", 0).pubkey());
+    }
+
+    #[cfg(feature = "token")]
+    #[test]
+    fn ata_address_matches_mainnet() {
+        use solana_program::pubkey;
+
+        // USDC tokenkeg ATA of 9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM
+        assert_eq!(
+            find_ata_address(
+                &pubkey!("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"),
+                &pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+                &spl_token::ID,
+            ),
+            pubkey!("FGETo8T8wMcN2wCjav8VK6eh3dLk63evNDPxzLSJra8B")
+        );
+    }
+
+    #[tokio::test]
+    async fn create_funded_wallets() {
+        let mut factory = TestWalletFactory::new("create_funded_wallets").with_lamports(12_345);
+        let (pt, wallets) = factory.create_all(ProgramTest::default(), &["alice", "bob"]);
+        let [alice, bob] = <[TestWallet; 2]>::try_from(wallets).unwrap();
+        assert_eq!(
+            alice.pubkey(),
+            test_keypair("create_funded_wallets", 0).pubkey()
+        );
+        assert_eq!(
+            bob.pubkey(),
+            test_keypair("create_funded_wallets", 1).pubkey()
+        );
+        assert_eq!(alice.to_string(), format!("alice ({})", alice.pubkey()));
+
+        let (mut bc, _payer, _rbh) = pt.start().await;
+        for w in [&alice, &bob] {
+            assert_eq!(bc.get_account_unwrapped(w.pubkey()).await.lamports, 12_345);
+        }
+    }
+
+    #[cfg(feature = "token")]
+    #[tokio::test]
+    async fn create_wallet_with_tokenkeg_account() {
+        use solana_program::program_pack::Pack;
+
+        use crate::token::{tokenkeg::TokenkegProgramTest, MockMintArgs};
+
+        let mint = Pubkey::new_unique();
+        let pt = ProgramTest::default().add_tokenkeg_mint_from_args(
+            mint,
+            MockMintArgs {
+                mint_authority: None,
+                freeze_authority: None,
+                supply: 1_000,
+                decimals: 9,
+            },
+        );
+        let (pt, alice) = TestWalletFactory::new("create_wallet_with_tokenkeg_account")
+            .with_tokenkeg_account(mint, 1_000)
+            .create(pt, "alice");
+        let ata = alice.ata(&mint);
+        assert_eq!(
+            ata,
+            find_ata_address(&alice.pubkey(), &mint, &spl_token::ID)
+        );
+
+        let (mut bc, _payer, _rbh) = pt.start().await;
+        let account = spl_token::state::Account::unpack(&bc.get_account_data(ata).await).unwrap();
+        assert_eq!(account.owner, alice.pubkey());
+        assert_eq!(account.amount, 1_000);
+    }
+}
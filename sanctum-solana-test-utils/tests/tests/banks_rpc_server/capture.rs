@@ -0,0 +1,53 @@
+use sanctum_solana_test_utils::{
+    capture::{CaptureError, CaptureRecipe, FixtureCapturer},
+    load_fixtures_dir,
+    spl_stake_pool::{SplStakePoolProgramTest, StakePoolFixtureBuilder, StakePoolFixtureValidator},
+    ExtendedProgramTest, FixturesDir,
+};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use solana_program_test::ProgramTest;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn capture_stake_pool_and_program() {
+    let pool = Pubkey::new_unique();
+    let fixture = StakePoolFixtureBuilder::spl(pool)
+        .n_validators(2, LAMPORTS_PER_SOL)
+        .validator(StakePoolFixtureValidator {
+            transient_staked_lamports: LAMPORTS_PER_SOL,
+            ..StakePoolFixtureValidator::new(Pubkey::new_unique(), LAMPORTS_PER_SOL)
+        })
+        .build();
+    let mut expected = fixture.clone().into_keyed_accounts();
+    expected.sort_by_key(|k| k.pubkey);
+    let program_id = Pubkey::new_unique();
+    let so_prog_data = vec![1, 2, 3, 4];
+    let pt = ProgramTest::default()
+        .add_stake_pool_fixture(fixture)
+        .add_upgradeable_program_from_data(program_id, &so_prog_data, None, 0);
+
+    let (client, _payer, _rbh) = setup(pt).await;
+    let out_dir = tempfile::tempdir().unwrap();
+    let capturer = FixtureCapturer::new(client, out_dir.path());
+
+    let written = capturer
+        .capture_recipe(CaptureRecipe::StakePool, pool)
+        .unwrap();
+    assert_eq!(written.len(), expected.len());
+    capturer.capture_program(program_id).unwrap();
+
+    let FixturesDir {
+        mut accounts,
+        programs,
+    } = load_fixtures_dir(out_dir.path()).unwrap();
+    accounts.sort_by_key(|k| k.pubkey);
+    assert_eq!(accounts, expected);
+    assert_eq!(programs, vec![(program_id, so_prog_data)]);
+
+    let missing = Pubkey::new_unique();
+    assert!(matches!(
+        capturer.capture_accounts(&[pool, missing]),
+        Err(CaptureError::AccountNotFound(pk)) if pk == missing
+    ));
+}
@@ -0,0 +1,172 @@
+use sanctum_solana_test_utils::banks_rpc_server::{BanksRpcServer, SanctumSetClockConfig};
+use serde_json::{json, Value};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    client_error::ClientErrorKind, rpc_client::RpcClient, rpc_request::RpcRequest,
+};
+use solana_program::{clock::Clock, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, sysvar};
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use tokio::net::TcpListener;
+
+async fn setup_with_context(pt: ProgramTest) -> RpcClient {
+    let ctx = pt.start_with_context().await;
+    let server = BanksRpcServer::with_context(ctx);
+    let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let port = tcp_listener.local_addr().unwrap().port();
+    let _jh = server.spawn(tcp_listener);
+    RpcClient::new(format!("http://127.0.0.1:{port}"))
+}
+
+fn cheat(client: &RpcClient, method: &'static str, params: Value) {
+    client
+        .send::<Value>(RpcRequest::Custom { method }, params)
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cheatcodes_set_account_and_airdrop() {
+    let client = setup_with_context(ProgramTest::default()).await;
+    let key = Pubkey::new_unique();
+    let account = Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: vec![1, 2, 3],
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: u64::MAX,
+    };
+    cheat(
+        &client,
+        "sanctum_setAccount",
+        json!([
+            key.to_string(),
+            UiAccount::encode(&key, &account, UiAccountEncoding::Base64, None, None)
+        ]),
+    );
+    assert_eq!(client.get_account(&key).unwrap(), account);
+
+    cheat(&client, "sanctum_airdrop", json!([key.to_string(), 1]));
+    assert_eq!(client.get_balance(&key).unwrap(), LAMPORTS_PER_SOL + 1);
+
+    let new = Pubkey::new_unique();
+    cheat(&client, "sanctum_airdrop", json!([new.to_string(), 2]));
+    let new_account = client.get_account(&new).unwrap();
+    assert_eq!(new_account.lamports, 2);
+    assert_eq!(new_account.owner, solana_program::system_program::ID);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cheatcodes_warp() {
+    let client = setup_with_context(ProgramTest::default()).await;
+
+    cheat(&client, "sanctum_warpToSlot", json!([1_000]));
+    assert_eq!(client.get_slot().unwrap(), 1_000);
+
+    let epoch = client.get_epoch_info().unwrap().epoch + 2;
+    cheat(&client, "sanctum_warpToEpoch", json!([epoch]));
+    let epoch_info = client.get_epoch_info().unwrap();
+    assert_eq!(epoch_info.epoch, epoch);
+    // 1 slot past the first slot of the epoch to end the epoch rewards interval
+    assert_eq!(epoch_info.slot_index, 1);
+
+    // cannot warp backwards
+    assert!(client
+        .send::<Value>(
+            RpcRequest::Custom {
+                method: "sanctum_warpToSlot"
+            },
+            json!([1])
+        )
+        .is_err());
+}
+
+#[cfg(all(feature = "stake", feature = "vote"))]
+#[tokio::test(flavor = "multi_thread")]
+async fn cheatcodes_warp_to_epoch_allows_delegation() {
+    use sanctum_solana_test_utils::{
+        stake::{SingleAuthorityAuthorized, StakeAccountFixtureBuilder, StakeLifecycle},
+        vote::{mock_validators, VoteProgramTest},
+        ExtendedProgramTest, IntoAccount,
+    };
+    use solana_program::stake::{instruction::delegate_stake, state::StakeStateV2};
+    use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+
+    let validators = mock_validators(1, 0);
+    let staker = Keypair::new();
+    let stake_addr = Pubkey::new_unique();
+    let client = setup_with_context(
+        ProgramTest::default()
+            .add_mock_validators(&validators)
+            .add_account_chained(
+                stake_addr,
+                StakeAccountFixtureBuilder::new(StakeLifecycle::Initialized, 0)
+                    .authorized(SingleAuthorityAuthorized(staker.pubkey()))
+                    .staked_lamports(10 * LAMPORTS_PER_SOL)
+                    .into_account(),
+            )
+            .add_account_chained(
+                staker.pubkey(),
+                Account::new(LAMPORTS_PER_SOL, 0, &solana_program::system_program::ID),
+            ),
+    )
+    .await;
+    cheat(&client, "sanctum_warpToEpoch", json!([1]));
+
+    // stake program instructions fail during the epoch rewards interval
+    let tx = Transaction::new_signed_with_payer(
+        &[delegate_stake(
+            &stake_addr,
+            &staker.pubkey(),
+            &validators[0].vote,
+        )],
+        Some(&staker.pubkey()),
+        &[&staker],
+        client.get_latest_blockhash().unwrap(),
+    );
+    client.send_and_confirm_transaction(&tx).unwrap();
+    let stake: StakeStateV2 =
+        bincode::deserialize(&client.get_account_data(&stake_addr).unwrap()).unwrap();
+    assert_eq!(stake.delegation().unwrap().activation_epoch, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cheatcodes_set_clock() {
+    const UNIX_TIMESTAMP: i64 = 1_700_000_000;
+
+    let client = setup_with_context(ProgramTest::default()).await;
+    let before: Clock =
+        bincode::deserialize(&client.get_account_data(&sysvar::clock::ID).unwrap()).unwrap();
+    cheat(
+        &client,
+        "sanctum_setClock",
+        json!([SanctumSetClockConfig {
+            unix_timestamp: Some(UNIX_TIMESTAMP),
+            ..Default::default()
+        }]),
+    );
+    let after: Clock =
+        bincode::deserialize(&client.get_account_data(&sysvar::clock::ID).unwrap()).unwrap();
+    assert_eq!(
+        after,
+        Clock {
+            unix_timestamp: UNIX_TIMESTAMP,
+            ..before
+        }
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cheatcodes_require_context() {
+    let (bc, _payer, _rbh) = ProgramTest::default().start().await;
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    let err = client
+        .send::<Value>(
+            RpcRequest::Custom {
+                method: "sanctum_warpToSlot",
+            },
+            json!([1_000]),
+        )
+        .unwrap_err();
+    assert!(matches!(err.kind(), ClientErrorKind::RpcError(_)));
+}
@@ -1,12 +1,42 @@
-use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
-use solana_client::rpc_client::RpcClient;
+use sanctum_solana_test_utils::banks_rpc_server::{
+    BanksRpcServer, IndexedProgramTest, SpawnedServers,
+};
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_client::RpcClient};
 use solana_program::hash::Hash;
-use solana_program_test::ProgramTest;
+use solana_program_test::BanksClient;
 use solana_sdk::signature::Keypair;
+use tokio::net::TcpListener;
 
-pub async fn setup(pt: ProgramTest) -> (RpcClient, Keypair, Hash) {
-    let (bc, payer, rbh) = pt.start().await;
-    let (port, _jh) = BanksRpcServer::spawn_random_unused(bc).await;
-    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+/// Pass an [`IndexedProgramTest`] for `getProgramAccounts` to return the accounts added to it
+pub async fn setup(pt: impl Into<IndexedProgramTest>) -> (RpcClient, Keypair, Hash) {
+    let (client, payer, rbh, _server) = setup_with_server(pt).await;
     (client, payer, rbh)
 }
+
+/// Also returns a clone of the spawned server for configuring it
+pub async fn setup_with_server(
+    pt: impl Into<IndexedProgramTest>,
+) -> (RpcClient, Keypair, Hash, BanksRpcServer) {
+    let (bc, payer, rbh, account_index) = pt.into().start().await;
+    let server = BanksRpcServer::new_with_account_index(bc, account_index);
+    let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let port = tcp_listener.local_addr().unwrap().port();
+    let _jh = server.clone().spawn(tcp_listener);
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    (client, payer, rbh, server)
+}
+
+/// Spawns both the HTTP and websocket pubsub servers
+pub async fn setup_with_pubsub(bc: BanksClient) -> (RpcClient, PubsubClient) {
+    let SpawnedServers {
+        rpc_port,
+        pubsub_port,
+        rpc_jh: _rpc_jh,
+        pubsub_jh: _pubsub_jh,
+    } = BanksRpcServer::spawn_random_unused_with_pubsub(bc).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{rpc_port}"));
+    let pubsub_client = PubsubClient::new(&format!("ws://127.0.0.1:{pubsub_port}"))
+        .await
+        .unwrap();
+    (client, pubsub_client)
+}
@@ -0,0 +1,173 @@
+use std::time::{Duration, Instant};
+
+use sanctum_solana_cli_utils::{HandleTxArgs, TxSendMode, TxSendingRpcClient};
+use sanctum_solana_client_utils::{get_compute_budget_ixs_auto, ComputeBudgetFeeLimit};
+use sanctum_solana_test_utils::banks_rpc_server::{
+    BanksRpcServer, Fault, FaultRule, PrioritizationFeeSource, RpcMethod,
+};
+use solana_client::{
+    client_error::ClientErrorKind, rpc_client::RpcClient, rpc_request::RpcError,
+    rpc_response::RpcPrioritizationFee,
+};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{commitment_config::CommitmentConfig, signer::Signer, transaction::Transaction};
+
+use crate::tests::banks_rpc_server::common::setup_with_server;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_injection_rpc_error() {
+    let (client, payer, _rbh, server) = setup_with_server(ProgramTest::default()).await;
+    server.set_faults(
+        RpcMethod::GetBalance,
+        [FaultRule::times(
+            Fault::RpcError(jsonrpc_core::Error::internal_error()),
+            1,
+        )],
+    );
+    let err = client.get_balance(&payer.pubkey()).unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            assert_eq!(*code, jsonrpc_core::ErrorCode::InternalError.code())
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+    assert!(client.get_balance(&payer.pubkey()).unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_injection_http_status() {
+    let (client, payer, _rbh, server) = setup_with_server(ProgramTest::default()).await;
+
+    server.set_faults(
+        RpcMethod::GetBalance,
+        [FaultRule::always(Fault::service_unavailable())],
+    );
+    let err = client.get_balance(&payer.pubkey()).unwrap_err();
+    assert!(matches!(err.kind(), ClientErrorKind::Reqwest(_)));
+    // other methods unaffected
+    client.get_latest_blockhash().unwrap();
+
+    server.clear_faults();
+    client.get_balance(&payer.pubkey()).unwrap();
+
+    // RpcClient retries on 429
+    server.set_faults(
+        RpcMethod::GetBalance,
+        [FaultRule::times(Fault::too_many_requests(), 1)],
+    );
+    client.get_balance(&payer.pubkey()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_injection_latency() {
+    const LATENCY: Duration = Duration::from_millis(500);
+
+    let (client, payer, _rbh, server) = setup_with_server(ProgramTest::default()).await;
+    server.set_faults(
+        RpcMethod::GetBalance,
+        [FaultRule::always(Fault::Latency(LATENCY))],
+    );
+    let start = Instant::now();
+    client.get_balance(&payer.pubkey()).unwrap();
+    assert!(start.elapsed() >= LATENCY);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_injection_stale_context_slot() {
+    const WARP_SLOT: u64 = 1_000;
+    const SLOTS_BEHIND: u64 = 10;
+
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    ctx.warp_to_slot(WARP_SLOT).unwrap();
+    let server = BanksRpcServer::new(ctx.banks_client.clone());
+    let tcp_listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .unwrap();
+    let port = tcp_listener.local_addr().unwrap().port();
+    let _jh = server.clone().spawn(tcp_listener);
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    server.set_faults(
+        RpcMethod::GetBalance,
+        [FaultRule::always(Fault::StaleContextSlot(SLOTS_BEHIND))],
+    );
+    let res = client
+        .get_balance_with_commitment(&ctx.payer.pubkey(), CommitmentConfig::confirmed())
+        .unwrap();
+    assert_eq!(res.context.slot, WARP_SLOT - SLOTS_BEHIND);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_injection_drop_transaction() {
+    let (client, payer, rbh, server) = setup_with_server(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    server.set_faults(
+        RpcMethod::SendTransaction,
+        [FaultRule::times(Fault::DropTransaction, 1)],
+    );
+    let sig = client.send_transaction(&tx).unwrap();
+    assert_eq!(sig, tx.signatures[0]);
+    assert!(client.get_signature_status(&sig).unwrap().is_none());
+    assert!(client
+        .get_account_with_commitment(&dst, Default::default())
+        .unwrap()
+        .value
+        .is_none());
+
+    // resending lands
+    client.send_transaction(&tx).unwrap();
+    assert!(client.get_signature_status(&sig).unwrap().unwrap().is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fault_injection_client_utils_retry() {
+    let (client, payer, rbh, server) = setup_with_server(ProgramTest::default()).await;
+    let ixs = [system_instruction::transfer(
+        &payer.pubkey(),
+        &Pubkey::new_unique(),
+        LAMPORTS_PER_SOL,
+    )];
+
+    server.set_prioritization_fee_source(PrioritizationFeeSource::Fixed(vec![
+        RpcPrioritizationFee {
+            slot: 0,
+            prioritization_fee: 1,
+        },
+    ]));
+    server.set_faults(
+        RpcMethod::GetRecentPrioritizationFees,
+        [FaultRule::times(Fault::service_unavailable(), 1)],
+    );
+    // first request fails, retry succeeds
+    for should_err in [true, false] {
+        let res = get_compute_budget_ixs_auto(
+            &client,
+            &payer.pubkey(),
+            &ixs,
+            &[],
+            &ComputeBudgetFeeLimit::MicroLamportsPerCu(u64::MAX),
+            1.2,
+        );
+        assert_eq!(res.is_err(), should_err);
+    }
+
+    server.set_faults(
+        RpcMethod::SimulateTransaction,
+        [FaultRule::times(Fault::too_many_requests(), 2)],
+    );
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &[&payer], rbh);
+    client
+        .handle_tx(&tx, TxSendMode::SimOnly, HandleTxArgs::cli_default())
+        .unwrap();
+}
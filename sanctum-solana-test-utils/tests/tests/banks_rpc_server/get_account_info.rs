@@ -1,7 +1,18 @@
+use jsonrpc_core::ErrorCode;
 use sanctum_solana_test_utils::{ExtendedProgramTest, Keyed};
+use serde_json::{json, Value};
+use solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    client_error::ClientErrorKind,
+    rpc_client::RpcClient,
+    rpc_config::RpcAccountInfoConfig,
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+    rpc_request::{RpcError, RpcRequest},
+    rpc_response::Response,
+};
 use solana_program::pubkey::Pubkey;
 use solana_program_test::ProgramTest;
-use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, signer::Signer};
 
 use crate::tests::banks_rpc_server::common::setup;
 
@@ -35,3 +46,215 @@ async fn get_account_info_basic() {
         None
     );
 }
+
+fn get_ui_account(
+    client: &RpcClient,
+    key: &Pubkey,
+    cfg: RpcAccountInfoConfig,
+) -> Option<UiAccount> {
+    client
+        .send::<Response<Option<UiAccount>>>(
+            RpcRequest::GetAccountInfo,
+            json!([key.to_string(), cfg]),
+        )
+        .unwrap()
+        .value
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_account_info_encodings() {
+    let [small, large] = [(); 2].map(|_| Pubkey::new_unique());
+    let [small_account, large_account] = [4, 256].map(|len| Account {
+        lamports: 1_000_000,
+        data: (0..len).map(|i| (i % 256) as u8).collect(),
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let pt = ProgramTest::default()
+        .add_keyed_account(Keyed {
+            pubkey: small,
+            account: small_account.clone(),
+        })
+        .add_keyed_account(Keyed {
+            pubkey: large,
+            account: large_account.clone(),
+        });
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    // no config, defaults to legacy binary (base58)
+    let default: Response<Option<UiAccount>> = client
+        .send(RpcRequest::GetAccountInfo, json!([small.to_string()]))
+        .unwrap();
+    let default = default.value.unwrap();
+    assert!(matches!(default.data, UiAccountData::LegacyBinary(_)));
+    assert_eq!(default.decode::<Account>().unwrap(), small_account);
+
+    for encoding in [
+        UiAccountEncoding::Base58,
+        UiAccountEncoding::Base64,
+        UiAccountEncoding::Base64Zstd,
+    ] {
+        let ui_account = get_ui_account(
+            &client,
+            &small,
+            RpcAccountInfoConfig {
+                encoding: Some(encoding),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        match &ui_account.data {
+            UiAccountData::Binary(_, e) => assert_eq!(*e, encoding),
+            d => panic!("Unexpected data {d:?}"),
+        }
+        assert_eq!(ui_account.decode::<Account>().unwrap(), small_account);
+    }
+
+    // base58 too large
+    let err = client
+        .send::<Value>(
+            RpcRequest::GetAccountInfo,
+            json!([
+                large.to_string(),
+                RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base58),
+                    ..Default::default()
+                }
+            ]),
+        )
+        .unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            assert_eq!(*code, ErrorCode::InvalidRequest.code())
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+
+    // base58 ok if sliced small enough
+    let sliced = get_ui_account(
+        &client,
+        &large,
+        RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base58),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 250,
+                length: 128,
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        sliced.decode::<Account>().unwrap().data,
+        large_account.data[250..]
+    );
+
+    // jsonParsed falls back to base64 for unparseable accounts
+    let parsed = get_ui_account(
+        &client,
+        &large,
+        RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::JsonParsed),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    match &parsed.data {
+        UiAccountData::Binary(_, e) => assert_eq!(*e, UiAccountEncoding::Base64),
+        d => panic!("Unexpected data {d:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_account_info_min_context_slot() {
+    let (client, payer, _rbh) = setup(ProgramTest::default()).await;
+    let slot = client
+        .get_account_with_config(&payer.pubkey(), Default::default())
+        .unwrap()
+        .context
+        .slot;
+
+    let ok = client
+        .get_account_with_config(
+            &payer.pubkey(),
+            RpcAccountInfoConfig {
+                min_context_slot: Some(slot),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(ok.value.is_some());
+
+    let err = client
+        .send::<Value>(
+            RpcRequest::GetAccountInfo,
+            json!([
+                payer.pubkey().to_string(),
+                RpcAccountInfoConfig {
+                    min_context_slot: Some(slot + 1),
+                    ..Default::default()
+                }
+            ]),
+        )
+        .unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            assert_eq!(*code, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED)
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+}
+
+#[cfg(feature = "token")]
+#[tokio::test(flavor = "multi_thread")]
+async fn get_account_info_json_parsed_token_account() {
+    use sanctum_solana_test_utils::token::{
+        tokenkeg::TokenkegProgramTest, MockMintArgs, MockTokenAccountArgs,
+    };
+    use solana_account_decoder::parse_account_data::ParsedAccount;
+
+    let [mint, token_account, authority] = [(); 3].map(|_| Pubkey::new_unique());
+    let pt = ProgramTest::default()
+        .add_tokenkeg_mint_from_args(
+            mint,
+            MockMintArgs {
+                mint_authority: None,
+                freeze_authority: None,
+                supply: 1_500_000,
+                decimals: 6,
+            },
+        )
+        .add_tokenkeg_account_from_args(
+            token_account,
+            MockTokenAccountArgs {
+                mint,
+                authority,
+                amount: 1_500_000,
+            },
+        );
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let ui_account = get_ui_account(
+        &client,
+        &token_account,
+        RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::JsonParsed),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let parsed = match ui_account.data {
+        UiAccountData::Json(ParsedAccount {
+            program, parsed, ..
+        }) => {
+            assert_eq!(program, "spl-token");
+            parsed
+        }
+        d => panic!("Unexpected data {d:?}"),
+    };
+    assert_eq!(parsed["type"], "account");
+    assert_eq!(parsed["info"]["owner"], authority.to_string());
+    assert_eq!(parsed["info"]["tokenAmount"]["decimals"], 6);
+    assert_eq!(parsed["info"]["tokenAmount"]["uiAmountString"], "1.5");
+}
@@ -0,0 +1,31 @@
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_balance_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    assert_eq!(client.get_balance(&dst).unwrap(), 0);
+
+    let payer_balance = client.get_balance(&payer.pubkey()).unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    client.send_and_confirm_transaction(&tx).unwrap();
+
+    assert_eq!(client.get_balance(&dst).unwrap(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        client.get_balance(&payer.pubkey()).unwrap(),
+        payer_balance - LAMPORTS_PER_SOL - 5000
+    );
+}
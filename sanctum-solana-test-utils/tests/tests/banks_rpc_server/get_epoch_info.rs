@@ -0,0 +1,27 @@
+use sanctum_solana_test_utils::banks_rpc_server::BanksRpcServer;
+use solana_client::rpc_client::RpcClient;
+use solana_program::epoch_schedule::EpochSchedule;
+use solana_program_test::ProgramTest;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_epoch_info_and_slot_after_warp() {
+    const WARP_SLOT: u64 = 1_000;
+
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    ctx.warp_to_slot(WARP_SLOT).unwrap();
+    let epoch_schedule: EpochSchedule = ctx.banks_client.get_sysvar().await.unwrap();
+    let (port, _jh) = BanksRpcServer::spawn_random_unused(ctx.banks_client.clone()).await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    assert_eq!(client.get_slot().unwrap(), WARP_SLOT);
+
+    let epoch_info = client.get_epoch_info().unwrap();
+    let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(WARP_SLOT);
+    assert_eq!(epoch_info.absolute_slot, WARP_SLOT);
+    assert_eq!(epoch_info.epoch, epoch);
+    assert_eq!(epoch_info.slot_index, slot_index);
+    assert_eq!(
+        epoch_info.slots_in_epoch,
+        epoch_schedule.get_slots_in_epoch(epoch)
+    );
+}
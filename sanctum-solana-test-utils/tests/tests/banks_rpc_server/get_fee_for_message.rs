@@ -0,0 +1,29 @@
+use solana_program::{
+    hash::Hash, message::Message, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_fee_for_message_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let other_signer = Keypair::new();
+    let ixs = [
+        system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), LAMPORTS_PER_SOL),
+        system_instruction::transfer(&other_signer.pubkey(), &Pubkey::new_unique(), 1),
+    ];
+
+    let one_signer = Message::new_with_blockhash(&ixs[..1], Some(&payer.pubkey()), &rbh);
+    assert_eq!(client.get_fee_for_message(&one_signer).unwrap(), 5000);
+
+    let two_signers = Message::new_with_blockhash(&ixs, Some(&payer.pubkey()), &rbh);
+    assert_eq!(client.get_fee_for_message(&two_signers).unwrap(), 10_000);
+
+    // RpcClient errors if result is None due to blockhash not found
+    let unknown_blockhash =
+        Message::new_with_blockhash(&ixs[..1], Some(&payer.pubkey()), &Hash::new_unique());
+    assert!(client.get_fee_for_message(&unknown_blockhash).is_err());
+}
@@ -0,0 +1,17 @@
+use sanctum_solana_test_utils::default_rent_exempt_lamports;
+use solana_program_test::ProgramTest;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_minimum_balance_for_rent_exemption_basic() {
+    let (client, _payer, _rbh) = setup(ProgramTest::default()).await;
+    for data_len in [0, 165, 10_000] {
+        assert_eq!(
+            client
+                .get_minimum_balance_for_rent_exemption(data_len)
+                .unwrap(),
+            default_rent_exempt_lamports(data_len)
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use sanctum_solana_test_utils::{
+    banks_rpc_server::{BanksRpcServer, IndexedProgramTest},
+    default_rent_exempt_lamports, ExtendedProgramTest, Keyed,
+};
+use serde_json::json;
+use solana_account_decoder::UiDataSliceConfig;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::RpcRequest,
+    rpc_response::{Response, RpcKeyedAccount},
+};
+use solana_program::{pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{account::Account, signature::Keypair, signer::Signer, transaction::Transaction};
+use tokio::net::TcpListener;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+fn program_owned_account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: default_rent_exempt_lamports(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
+
+fn sorted_pubkeys(accounts: &[(Pubkey, Account)]) -> Vec<Pubkey> {
+    let mut res: Vec<Pubkey> = accounts.iter().map(|(pk, _)| *pk).collect();
+    res.sort();
+    res
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_filters() {
+    let program_id = Pubkey::new_unique();
+    let [a, b, c, other] = [(); 4].map(|_| Pubkey::new_unique());
+    let pt = IndexedProgramTest::default()
+        .add_keyed_account(Keyed {
+            pubkey: a,
+            account: program_owned_account(program_id, vec![1, 2, 3, 4]),
+        })
+        .add_keyed_account(Keyed {
+            pubkey: b,
+            account: program_owned_account(program_id, vec![1, 2, 5, 6, 7]),
+        })
+        .add_keyed_account(Keyed {
+            pubkey: c,
+            account: program_owned_account(program_id, vec![9, 2, 3, 4]),
+        })
+        .add_keyed_account(Keyed {
+            pubkey: other,
+            account: program_owned_account(Pubkey::new_unique(), vec![1, 2, 3, 4]),
+        });
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let all = client.get_program_accounts(&program_id).unwrap();
+    let mut expected = vec![a, b, c];
+    expected.sort();
+    assert_eq!(sorted_pubkeys(&all), expected);
+
+    let data_size = client
+        .get_program_accounts_with_config(
+            &program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::DataSize(4)]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let mut expected = vec![a, c];
+    expected.sort();
+    assert_eq!(sorted_pubkeys(&data_size), expected);
+
+    let memcmp = client
+        .get_program_accounts_with_config(
+            &program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![
+                    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &[1, 2])),
+                    RpcFilterType::DataSize(4),
+                ]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(sorted_pubkeys(&memcmp), vec![a]);
+
+    let sliced = client
+        .get_program_accounts_with_config(
+            &program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    2,
+                    vec![5],
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: 3,
+                        length: 10,
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(sliced.len(), 1);
+    assert_eq!(sliced[0].0, b);
+    assert_eq!(sliced[0].1.data, vec![6, 7]);
+
+    let with_ctx: Response<Vec<RpcKeyedAccount>> = client
+        .send(
+            RpcRequest::GetProgramAccounts,
+            json!([
+                program_id.to_string(),
+                RpcProgramAccountsConfig {
+                    filters: Some(vec![RpcFilterType::DataSize(5)]),
+                    with_context: Some(true),
+                    ..Default::default()
+                }
+            ]),
+        )
+        .unwrap();
+    assert_eq!(with_ctx.value.len(), 1);
+    assert_eq!(with_ctx.value[0].pubkey, b.to_string());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_tx_written() {
+    let program_id = Pubkey::new_unique();
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    assert!(client.get_program_accounts(&program_id).unwrap().is_empty());
+
+    let new_account = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &payer.pubkey(),
+            &new_account.pubkey(),
+            default_rent_exempt_lamports(8),
+            8,
+            &program_id,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &new_account],
+        rbh,
+    );
+    client.send_and_confirm_transaction(&tx).unwrap();
+
+    let res = client.get_program_accounts(&program_id).unwrap();
+    assert_eq!(sorted_pubkeys(&res), vec![new_account.pubkey()]);
+    assert_eq!(res[0].1.data, vec![0; 8]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_registered() {
+    let program_id = Pubkey::new_unique();
+    let [registered, unregistered] = [(); 2].map(|_| Pubkey::new_unique());
+    let mut pt = ProgramTest::default();
+    // ProgramTest::add_account() directly instead of via ExtendedProgramTest
+    // so that these accounts are not automatically tracked
+    for pk in [registered, unregistered] {
+        pt.add_account(pk, program_owned_account(program_id, vec![1]));
+    }
+    let (bc, _payer, _rbh) = pt.start().await;
+
+    let server = BanksRpcServer::new(bc);
+    let tcp_listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let port = tcp_listener.local_addr().unwrap().port();
+    let _jh = server.clone().spawn(tcp_listener);
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    assert!(client.get_program_accounts(&program_id).unwrap().is_empty());
+    server.register_accounts([registered]);
+    let res = client.get_program_accounts(&program_id).unwrap();
+    assert_eq!(sorted_pubkeys(&res), vec![registered]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_program_accounts_not_shared_across_program_tests() {
+    let program_id = Pubkey::new_unique();
+    let [indexed, unindexed] = [(); 2].map(|_| Pubkey::new_unique());
+    let indexed_pt = IndexedProgramTest::default().add_keyed_account(Keyed {
+        pubkey: indexed,
+        account: program_owned_account(program_id, vec![1]),
+    });
+    // ExtendedProgramTest on a plain ProgramTest does not record accounts anywhere
+    let plain_pt = ProgramTest::default().add_keyed_account(Keyed {
+        pubkey: unindexed,
+        account: program_owned_account(program_id, vec![1]),
+    });
+    let (indexed_client, _payer, _rbh) = setup(indexed_pt).await;
+    let (plain_client, _payer, _rbh) = setup(plain_pt).await;
+
+    let res = indexed_client.get_program_accounts(&program_id).unwrap();
+    assert_eq!(sorted_pubkeys(&res), vec![indexed]);
+    assert!(plain_client
+        .get_program_accounts(&program_id)
+        .unwrap()
+        .is_empty());
+}
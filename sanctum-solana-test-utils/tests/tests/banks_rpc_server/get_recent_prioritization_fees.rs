@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use sanctum_solana_client_utils::{
+    buffer_compute_units, calc_compute_unit_price, calc_slot_weighted_median_prioritization_fees,
+    estimate_compute_unit_limit, get_compute_budget_ixs_auto, to_est_cu_sim_tx,
+    ComputeBudgetFeeLimit, ComputeBudgetIxs,
+};
+use sanctum_solana_test_utils::banks_rpc_server::PrioritizationFeeSource;
+use solana_client::rpc_response::RpcPrioritizationFee;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::signer::Signer;
+
+use crate::tests::banks_rpc_server::common::setup_with_server;
+
+fn samples(fees: &[u64]) -> Vec<RpcPrioritizationFee> {
+    fees.iter()
+        .enumerate()
+        .map(|(i, fee)| RpcPrioritizationFee {
+            slot: u64::try_from(i).unwrap(),
+            prioritization_fee: *fee,
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_recent_prioritization_fees_sources() {
+    let (client, _payer, _rbh, server) = setup_with_server(ProgramTest::default()).await;
+    let [hot, cold] = [(); 2].map(|_| Pubkey::new_unique());
+
+    assert!(client
+        .get_recent_prioritization_fees(&[])
+        .unwrap()
+        .is_empty());
+
+    server.set_prioritization_fee_source(PrioritizationFeeSource::Fixed(samples(&[0, 100, 200])));
+    assert_eq!(
+        client.get_recent_prioritization_fees(&[hot]).unwrap(),
+        samples(&[0, 100, 200])
+    );
+
+    server.set_prioritization_fee_source(PrioritizationFeeSource::PerAccount {
+        samples: samples(&[0, 100, 200]),
+        account_fees: HashMap::from([(hot, 150)]),
+    });
+    assert_eq!(
+        client.get_recent_prioritization_fees(&[cold]).unwrap(),
+        samples(&[0, 100, 200])
+    );
+    assert_eq!(
+        client.get_recent_prioritization_fees(&[cold, hot]).unwrap(),
+        samples(&[150, 150, 200])
+    );
+
+    server.set_prioritization_fee_source(PrioritizationFeeSource::callback(|accounts| {
+        samples(&[u64::try_from(accounts.len()).unwrap()])
+    }));
+    assert_eq!(
+        client.get_recent_prioritization_fees(&[cold, hot]).unwrap(),
+        samples(&[2])
+    );
+
+    assert!(client
+        .get_recent_prioritization_fees(&[Pubkey::new_unique(); 129])
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_compute_budget_ixs_auto_offline() {
+    const CU_BUFFER_RATIO: f64 = 1.2;
+
+    let (client, payer, _rbh, server) = setup_with_server(ProgramTest::default()).await;
+    let ixs = [system_instruction::transfer(
+        &payer.pubkey(),
+        &Pubkey::new_unique(),
+        LAMPORTS_PER_SOL,
+    )];
+    let fee_samples = samples(&[0, 10_000, 10_000, 20_000]);
+    let median = calc_slot_weighted_median_prioritization_fees(&fee_samples).unwrap();
+    server.set_prioritization_fee_source(PrioritizationFeeSource::Fixed(fee_samples));
+
+    let cus = estimate_compute_unit_limit(
+        &client,
+        &to_est_cu_sim_tx(&payer.pubkey(), &ixs, &[]).unwrap(),
+    )
+    .unwrap();
+    let cu_limit = buffer_compute_units(cus, CU_BUFFER_RATIO);
+
+    // fee limit not hit
+    let uncapped = get_compute_budget_ixs_auto(
+        &client,
+        &payer.pubkey(),
+        &ixs,
+        &[],
+        &ComputeBudgetFeeLimit::MicroLamportsPerCu(u64::MAX),
+        CU_BUFFER_RATIO,
+    )
+    .unwrap();
+    assert_eq!(uncapped, ComputeBudgetIxs::new(cu_limit, median));
+
+    // fee limit hit
+    let fee_limit = ComputeBudgetFeeLimit::TotalLamports(1);
+    let capped = get_compute_budget_ixs_auto(
+        &client,
+        &payer.pubkey(),
+        &ixs,
+        &[],
+        &fee_limit,
+        CU_BUFFER_RATIO,
+    )
+    .unwrap();
+    let capped_price = calc_compute_unit_price(cu_limit, 1);
+    assert!(capped_price < median);
+    assert_eq!(capped, ComputeBudgetIxs::new(cu_limit, capped_price));
+}
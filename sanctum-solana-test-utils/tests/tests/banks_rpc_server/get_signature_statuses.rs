@@ -0,0 +1,70 @@
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use solana_transaction_status::TransactionConfirmationStatus;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_signature_statuses_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+
+    let statuses = client
+        .get_signature_statuses(&[sig, Signature::new_unique()])
+        .unwrap()
+        .value;
+    let status = statuses[0].as_ref().unwrap();
+    assert!(status.err.is_none());
+    assert!(status.status.is_ok());
+    assert_eq!(
+        status.confirmation_status,
+        Some(TransactionConfirmationStatus::Finalized)
+    );
+    assert!(statuses[1].is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_signature_statuses_failed_tx() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let broke = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &broke.pubkey(),
+            &Pubkey::new_unique(),
+            1,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &broke],
+        rbh,
+    );
+    let sig = client
+        .send_transaction_with_config(
+            &tx,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let status = client.get_signature_statuses(&[sig]).unwrap().value[0]
+        .clone()
+        .unwrap();
+    assert!(status.err.is_some());
+    assert!(status.status.is_err());
+}
@@ -0,0 +1,45 @@
+use sanctum_solana_test_utils::{
+    token::{tokenkeg::TokenkegProgramTest, MockMintArgs, MockTokenAccountArgs},
+    ExtendedProgramTest,
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTest;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_account_balance_basic() {
+    let [mint, token_account, not_token_account] = [(); 3].map(|_| Pubkey::new_unique());
+    let pt = ProgramTest::default()
+        .add_tokenkeg_mint_from_args(
+            mint,
+            MockMintArgs {
+                mint_authority: None,
+                freeze_authority: None,
+                supply: 1_500_000,
+                decimals: 6,
+            },
+        )
+        .add_tokenkeg_account_from_args(
+            token_account,
+            MockTokenAccountArgs {
+                mint,
+                authority: Pubkey::new_unique(),
+                amount: 1_500_000,
+            },
+        )
+        .add_system_account(not_token_account, 1_000_000);
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let balance = client.get_token_account_balance(&token_account).unwrap();
+    assert_eq!(balance.amount, "1500000");
+    assert_eq!(balance.decimals, 6);
+    assert_eq!(balance.ui_amount_string, "1.5");
+
+    assert!(client
+        .get_token_account_balance(&not_token_account)
+        .is_err());
+    assert!(client
+        .get_token_account_balance(&Pubkey::new_unique())
+        .is_err());
+}
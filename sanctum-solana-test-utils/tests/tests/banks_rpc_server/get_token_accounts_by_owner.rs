@@ -0,0 +1,81 @@
+use sanctum_solana_test_utils::{
+    banks_rpc_server::IndexedProgramTest,
+    token::{tokenkeg::TokenkegProgramTest, MockMintArgs, MockTokenAccountArgs},
+};
+use solana_account_decoder::UiAccountData;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_program::pubkey::Pubkey;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+const MOCK_MINT_ARGS: MockMintArgs = MockMintArgs {
+    mint_authority: None,
+    freeze_authority: None,
+    supply: 0,
+    decimals: 9,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_token_accounts_by_owner_basic() {
+    let [m1, m2, owner, other_owner, o1, o2, o3] = [(); 7].map(|_| Pubkey::new_unique());
+    let pt = IndexedProgramTest::default()
+        .add_tokenkeg_mint_from_args(m1, MOCK_MINT_ARGS)
+        .add_tokenkeg_mint_from_args(m2, MOCK_MINT_ARGS)
+        .add_tokenkeg_account_from_args(
+            o1,
+            MockTokenAccountArgs {
+                mint: m1,
+                authority: owner,
+                amount: 1,
+            },
+        )
+        .add_tokenkeg_account_from_args(
+            o2,
+            MockTokenAccountArgs {
+                mint: m2,
+                authority: owner,
+                amount: 2,
+            },
+        )
+        .add_tokenkeg_account_from_args(
+            o3,
+            MockTokenAccountArgs {
+                mint: m1,
+                authority: other_owner,
+                amount: 3,
+            },
+        );
+    let (client, _payer, _rbh) = setup(pt).await;
+
+    let by_mint = client
+        .get_token_accounts_by_owner(&owner, TokenAccountsFilter::Mint(m1))
+        .unwrap();
+    assert_eq!(by_mint.len(), 1);
+    assert_eq!(by_mint[0].pubkey, o1.to_string());
+    // RpcClient requests jsonParsed
+    match &by_mint[0].account.data {
+        UiAccountData::Json(parsed) => assert_eq!(
+            parsed.parsed["info"]["tokenAmount"]["uiAmountString"],
+            "0.000000001"
+        ),
+        d => panic!("Unexpected data {d:?}"),
+    }
+
+    let mut by_program_id: Vec<String> = client
+        .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(spl_token::ID))
+        .unwrap()
+        .into_iter()
+        .map(|keyed| keyed.pubkey)
+        .collect();
+    by_program_id.sort();
+    let mut expected = vec![o1.to_string(), o2.to_string()];
+    expected.sort();
+    assert_eq!(by_program_id, expected);
+
+    assert!(client
+        .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(Pubkey::new_unique()))
+        .is_err());
+    assert!(client
+        .get_token_accounts_by_owner(&owner, TokenAccountsFilter::Mint(Pubkey::new_unique()))
+        .is_err());
+}
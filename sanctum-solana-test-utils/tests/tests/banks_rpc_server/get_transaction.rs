@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+
+use sanctum_solana_test_utils::{ExtendedProgramTest, Keyed};
+use serde_json::{json, Value};
+use solana_client::{
+    client_error::ClientErrorKind,
+    rpc_config::RpcTransactionConfig,
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
+    rpc_request::{RpcError, RpcRequest},
+};
+use solana_program::{
+    address_lookup_table::{
+        self,
+        state::{AddressLookupTable, LookupTableMeta},
+        AddressLookupTableAccount,
+    },
+    message::{v0, VersionedMessage},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    signature::Signature,
+    signer::Signer,
+    transaction::{Transaction, TransactionVersion, VersionedTransaction},
+};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+const SIG_FEE: u64 = 5000;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_transaction_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+
+    for encoding in [UiTransactionEncoding::Json, UiTransactionEncoding::Base64] {
+        let res = client
+            .get_transaction_with_config(
+                &sig,
+                RpcTransactionConfig {
+                    encoding: Some(encoding),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // only binary encodings can be decoded
+        if let Some(decoded) = res.transaction.transaction.decode() {
+            assert_eq!(decoded, VersionedTransaction::from(tx.clone()));
+        }
+        let meta = res.transaction.meta.unwrap();
+        assert!(meta.err.is_none());
+        assert_eq!(meta.fee, SIG_FEE);
+        assert_eq!(
+            meta.pre_balances[0] - meta.post_balances[0],
+            LAMPORTS_PER_SOL + SIG_FEE
+        );
+        assert_eq!(meta.post_balances[1], LAMPORTS_PER_SOL);
+        match meta.log_messages {
+            OptionSerializer::Some(logs) => assert!(!logs.is_empty()),
+            _ => panic!("Missing log messages"),
+        }
+    }
+
+    let unknown: Option<Value> = client
+        .send(
+            RpcRequest::GetTransaction,
+            json!([Signature::new_unique().to_string()]),
+        )
+        .unwrap();
+    assert!(unknown.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_transaction_v0_lookup_table() {
+    let lut = Pubkey::new_unique();
+    let dst = Pubkey::new_unique();
+    let pt = ProgramTest::default().add_keyed_account(Keyed {
+        pubkey: lut,
+        account: Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: AddressLookupTable {
+                meta: LookupTableMeta::default(),
+                addresses: Cow::Owned(vec![dst]),
+            }
+            .serialize_for_tests()
+            .unwrap(),
+            owner: address_lookup_table::program::id(),
+            executable: false,
+            rent_epoch: u64::MAX,
+        },
+    });
+    let (client, payer, rbh) = setup(pt).await;
+
+    let message = v0::Message::try_compile(
+        &payer.pubkey(),
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        &[AddressLookupTableAccount {
+            key: lut,
+            addresses: vec![dst],
+        }],
+        rbh,
+    )
+    .unwrap();
+    let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap();
+    let sig = client.send_and_confirm_transaction(&tx).unwrap();
+    assert_eq!(client.get_account(&dst).unwrap().lamports, LAMPORTS_PER_SOL);
+
+    let err = client
+        .get_transaction_with_config(&sig, RpcTransactionConfig::default())
+        .unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            assert_eq!(*code, JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION)
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+
+    let res = client
+        .get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                max_supported_transaction_version: Some(0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(res.transaction.version, Some(TransactionVersion::Number(0)));
+    let meta = res.transaction.meta.unwrap();
+    assert_eq!(meta.fee, SIG_FEE);
+    match meta.loaded_addresses {
+        OptionSerializer::Some(loaded) => {
+            assert_eq!(loaded.writable, vec![dst.to_string()]);
+            assert!(loaded.readonly.is_empty());
+        }
+        _ => panic!("Missing loaded addresses"),
+    }
+    // static keys: [payer, system program], loaded writable: [dst]
+    assert_eq!(meta.post_balances[2], LAMPORTS_PER_SOL);
+}
@@ -0,0 +1,28 @@
+use solana_program::hash::Hash;
+use solana_program_test::ProgramTest;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn is_blockhash_valid_basic() {
+    let (client, _payer, rbh) = setup(ProgramTest::default()).await;
+    assert!(client
+        .is_blockhash_valid(&rbh, CommitmentConfig::processed())
+        .unwrap());
+    assert!(!client
+        .is_blockhash_valid(&Hash::new_unique(), CommitmentConfig::processed())
+        .unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn is_blockhash_valid_other_bank() {
+    let (client, _payer, _rbh) = setup(ProgramTest::default()).await;
+    let (_other_bc, _other_payer, other_rbh) = ProgramTest::default().start().await;
+    assert!(!client
+        .is_blockhash_valid(&other_rbh, CommitmentConfig::processed())
+        .unwrap());
+    assert!(!client
+        .is_blockhash_valid(&Hash::default(), CommitmentConfig::processed())
+        .unwrap());
+}
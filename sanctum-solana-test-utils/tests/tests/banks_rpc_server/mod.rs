@@ -1,5 +1,26 @@
+#[cfg(feature = "capture")]
+mod capture;
+mod cheatcodes;
 mod common;
+mod fault_injection;
 mod get_account_info;
+mod get_balance;
+mod get_epoch_info;
+mod get_fee_for_message;
 mod get_latest_blockhash;
+mod get_minimum_balance_for_rent_exemption;
 mod get_multiple_accounts;
+mod get_program_accounts;
+mod get_recent_prioritization_fees;
+mod get_signature_statuses;
+#[cfg(feature = "token")]
+mod get_token_account_balance;
+#[cfg(feature = "token")]
+mod get_token_accounts_by_owner;
+mod get_transaction;
 mod get_version;
+mod is_blockhash_valid;
+mod pubsub;
+mod replay;
+mod send_transaction;
+mod simulate_transaction;
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::RpcAccountInfoConfig,
+    rpc_response::{ProcessedSignatureResult, RpcSignatureResult},
+};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use tokio::time::timeout;
+
+use crate::tests::banks_rpc_server::common::setup_with_pubsub;
+
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pubsub_signature_subscribe() {
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (client, pubsub_client) = setup_with_pubsub(bc).await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    let sig = tx.signatures[0];
+    let (mut notifs, _unsub) = pubsub_client.signature_subscribe(&sig, None).await.unwrap();
+    client.send_transaction(&tx).unwrap();
+    let notif = timeout(NOTIFICATION_TIMEOUT, notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        notif.value,
+        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: None })
+    );
+
+    // already processed signatures are notified immediately
+    let (mut notifs, _unsub) = pubsub_client.signature_subscribe(&sig, None).await.unwrap();
+    let notif = timeout(NOTIFICATION_TIMEOUT, notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        notif.value,
+        RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: None })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pubsub_account_subscribe() {
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (client, pubsub_client) = setup_with_pubsub(bc).await;
+    let dst = Pubkey::new_unique();
+    let (mut notifs, unsub) = pubsub_client
+        .account_subscribe(
+            &dst,
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    for lamports in [LAMPORTS_PER_SOL, LAMPORTS_PER_SOL + 1] {
+        let tx = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &dst,
+                lamports,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            rbh,
+        );
+        client.send_transaction(&tx).unwrap();
+    }
+    for expected_lamports in [LAMPORTS_PER_SOL, 2 * LAMPORTS_PER_SOL + 1] {
+        let notif = timeout(NOTIFICATION_TIMEOUT, notifs.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notif.value.lamports, expected_lamports);
+        assert_eq!(
+            notif.value.owner,
+            solana_program::system_program::ID.to_string()
+        );
+    }
+    unsub().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pubsub_account_subscribe_default_encoding_large_account() {
+    let (bc, payer, rbh) = ProgramTest::default().start().await;
+    let (client, pubsub_client) = setup_with_pubsub(bc).await;
+    let new_account = Keypair::new();
+    let owner = Pubkey::new_unique();
+    // binary (base58) encoding is the default, which is limited to 128 bytes
+    let (mut notifs, unsub) = pubsub_client
+        .account_subscribe(&new_account.pubkey(), None)
+        .await
+        .unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &payer.pubkey(),
+            &new_account.pubkey(),
+            LAMPORTS_PER_SOL,
+            165,
+            &owner,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &new_account],
+        rbh,
+    );
+    client.send_transaction(&tx).unwrap();
+    let notif = timeout(NOTIFICATION_TIMEOUT, notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notif.value.lamports, LAMPORTS_PER_SOL);
+    assert_eq!(notif.value.owner, owner.to_string());
+    assert_eq!(notif.value.space, Some(165));
+    unsub().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pubsub_slot_subscribe() {
+    let mut ctx = ProgramTest::default().start_with_context().await;
+    let (_client, pubsub_client) = setup_with_pubsub(ctx.banks_client.clone()).await;
+    let (mut notifs, _unsub) = pubsub_client.slot_subscribe().await.unwrap();
+    ctx.warp_to_slot(1000).unwrap();
+    let notif = timeout(NOTIFICATION_TIMEOUT, notifs.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notif.slot, 1000);
+    assert_eq!(notif.root, 1000);
+}
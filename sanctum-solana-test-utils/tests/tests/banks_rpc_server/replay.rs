@@ -0,0 +1,94 @@
+use sanctum_solana_test_utils::banks_rpc_server::{
+    read_recording, RecordedExchange, RpcReplayServer,
+};
+use serde_json::{json, Value};
+use solana_client::{rpc_client::RpcClient, rpc_request::RpcRequest};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use tempfile::NamedTempFile;
+
+use crate::tests::banks_rpc_server::common::setup_with_server;
+
+/// Runs the same requests against both the recording and replaying server
+fn session(client: &RpcClient, payer: &Keypair, dst: &Pubkey) -> (u64, u64, bool) {
+    let payer_balance = client.get_balance(&payer.pubkey()).unwrap();
+    let rbh = client.get_latest_blockhash().unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[payer],
+        rbh,
+    );
+    let sig = client.send_transaction(&tx).unwrap();
+    let landed = client.get_signature_status(&sig).unwrap().unwrap().is_ok();
+    let dst_balance = client.get_balance(dst).unwrap();
+    (payer_balance, dst_balance, landed)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn record_and_replay() {
+    let (client, payer, _rbh, server) = setup_with_server(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    let recording = NamedTempFile::new().unwrap();
+
+    server.start_recording(recording.path()).unwrap();
+    let recorded = session(&client, &payer, &dst);
+    server.stop_recording();
+    assert_eq!(recorded.1, LAMPORTS_PER_SOL);
+    assert!(recorded.2);
+
+    let exchanges = read_recording(recording.path()).unwrap();
+    assert!(exchanges.len() >= 5);
+    // not recorded after stopping
+    client.get_balance(&Pubkey::new_unique()).unwrap();
+    assert_eq!(read_recording(recording.path()).unwrap(), exchanges);
+
+    let (port, _jh) = RpcReplayServer::from_recording(recording.path())
+        .unwrap()
+        .spawn_random_unused()
+        .await;
+    let replay_client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+    assert_eq!(session(&replay_client, &payer, &dst), recorded);
+
+    // unrecorded requests error
+    assert!(replay_client.get_balance(&Pubkey::new_unique()).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replay_matches_parsed_params_and_errors_once_exhausted() {
+    let key = Pubkey::new_unique().to_string();
+    let exchange = RecordedExchange {
+        request: json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getBalance",
+            "params": [key, { "minContextSlot": 0, "commitment": "confirmed" }]
+        }),
+        response: json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": 42 }
+        }),
+    };
+    let (port, _jh) = RpcReplayServer::new([exchange]).spawn_random_unused().await;
+    let client = RpcClient::new(format!("http://127.0.0.1:{port}"));
+
+    // same params with keys in a different order
+    let params = json!([key, { "commitment": "confirmed", "minContextSlot": 0 }]);
+    let resp: Value = client.send(RpcRequest::GetBalance, params.clone()).unwrap();
+    assert_eq!(resp["value"], 42);
+
+    let err = client
+        .send::<Value>(RpcRequest::GetBalance, params)
+        .unwrap_err();
+    assert!(err.to_string().contains("getBalance"), "{err}");
+    let err = client
+        .send::<Value>(RpcRequest::GetSlot, Value::Null)
+        .unwrap_err();
+    assert!(err.to_string().contains("getSlot"), "{err}");
+}
@@ -0,0 +1,131 @@
+use solana_client::{
+    client_error::ClientErrorKind,
+    rpc_config::RpcSendTransactionConfig,
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+        JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE,
+    },
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_transaction_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+
+    let cfgs = [
+        RpcSendTransactionConfig::default(),
+        RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..Default::default()
+        },
+        RpcSendTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base58),
+            ..Default::default()
+        },
+    ];
+    for (i, cfg) in cfgs.into_iter().enumerate() {
+        // vary amount so that tx signatures are unique
+        let lamports = LAMPORTS_PER_SOL + u64::try_from(i).unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &dst,
+                lamports,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            rbh,
+        );
+        let sig = client.send_transaction_with_config(&tx, cfg).unwrap();
+        assert_eq!(sig, tx.signatures[0]);
+    }
+    assert_eq!(
+        client.get_account(&dst).unwrap().lamports,
+        3 * LAMPORTS_PER_SOL + 3
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_transaction_preflight_failure() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let broke = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &broke.pubkey(),
+            &Pubkey::new_unique(),
+            1,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &broke],
+        rbh,
+    );
+    let err = client.send_transaction(&tx).unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, data, .. }) => {
+            assert_eq!(
+                *code,
+                JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE
+            );
+            match data {
+                RpcResponseErrorData::SendTransactionPreflightFailure(res) => {
+                    assert!(res.err.is_some());
+                    assert!(!res.logs.as_ref().unwrap().is_empty());
+                }
+                _ => panic!("Unexpected data {data:?}"),
+            }
+        }
+        _ => panic!("Unexpected err {err}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_transaction_sig_verify_failure() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    let mut tx = Transaction::new_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+    );
+    tx.message.recent_blockhash = rbh;
+    tx.signatures = vec![Signature::new_unique()];
+
+    let err = client.send_transaction(&tx).unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => assert_eq!(
+            *code,
+            JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE
+        ),
+        _ => panic!("Unexpected err {err}"),
+    }
+
+    // dropped instead of processed if preflight skipped
+    client
+        .send_transaction_with_config(
+            &tx,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(client
+        .get_account_with_commitment(&dst, Default::default())
+        .unwrap()
+        .value
+        .is_none());
+}
@@ -0,0 +1,161 @@
+use sanctum_solana_cli_utils::{HandleTxArgs, TxSendMode, TxSendingRpcClient};
+use solana_client::{
+    client_error::ClientErrorKind, rpc_config::RpcSimulateTransactionConfig,
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE,
+    rpc_request::RpcError,
+};
+use solana_program::{
+    hash::Hash, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{signature::Signature, signer::Signer, transaction::Transaction};
+
+use crate::tests::banks_rpc_server::common::setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_basic() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+
+    let res = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                inner_instructions: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .value;
+    assert!(res.err.is_none());
+    assert!(!res.logs.unwrap().is_empty());
+    assert!(res.units_consumed.unwrap() > 0);
+    assert!(res.return_data.is_none());
+    assert!(res.replacement_blockhash.is_none());
+    assert_eq!(res.inner_instructions.unwrap().len(), 0);
+
+    // simulation does not affect state
+    assert!(client
+        .get_account_with_commitment(&dst, Default::default())
+        .unwrap()
+        .value
+        .is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_replace_recent_blockhash() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let mut tx = Transaction::new_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+    );
+    tx.message.recent_blockhash = Hash::default();
+    tx.signatures = vec![Signature::default()];
+
+    let res = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                replace_recent_blockhash: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .value;
+    assert!(res.err.is_none());
+    assert_eq!(
+        res.replacement_blockhash.unwrap().blockhash,
+        rbh.to_string()
+    );
+
+    let res = client
+        .simulate_transaction_with_config(&tx, RpcSimulateTransactionConfig::default())
+        .unwrap()
+        .value;
+    assert!(res.err.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulate_transaction_sig_verify_failure() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let mut tx = Transaction::new_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+    );
+    tx.message.recent_blockhash = rbh;
+    tx.signatures = vec![Signature::new_unique()];
+
+    let err = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => assert_eq!(
+            *code,
+            JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE
+        ),
+        _ => panic!("Unexpected err {err}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_tx_sim_only() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    client
+        .handle_tx(&tx, TxSendMode::SimOnly, HandleTxArgs::cli_default())
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_tx_send_actual() {
+    let (client, payer, rbh) = setup(ProgramTest::default()).await;
+    let dst = Pubkey::new_unique();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &dst,
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        rbh,
+    );
+    client
+        .handle_tx(&tx, TxSendMode::SendActual, HandleTxArgs::cli_default())
+        .unwrap();
+    assert_eq!(client.get_balance(&dst).unwrap(), LAMPORTS_PER_SOL);
+}